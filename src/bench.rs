@@ -0,0 +1,187 @@
+use std::time::Duration;
+use std::time::Instant;
+
+use term2d::model::point::Point;
+use term2d::view::canvas::Canvas;
+
+use crate::common::args::Args;
+use crate::common::args::BenchArgs;
+use crate::common::bench_setup::BenchSetup;
+use crate::renderer::canvas::color_mode::ColorMode;
+use crate::renderer::Renderer;
+use crate::state::State;
+
+/// The measurements of one setup at one size, the phases are measured for
+/// every generation: computing it, drawing the world to the canvas and
+/// converting the canvas to escape sequences.
+#[derive(Clone, Debug)]
+struct BenchResult {
+    ansi: Duration,
+    draw: Duration,
+    generations: u64,
+    population: usize,
+    setup: String,
+    size: Point,
+    update: Duration,
+}
+
+impl BenchResult {
+    fn generations_per_second(&self) -> f64 {
+        self.generations as f64 / self.update.as_secs_f64()
+    }
+
+    fn cells_per_second(&self) -> f64 {
+        self.generations_per_second() * (self.size.width() * self.size.height()) as f64
+    }
+
+    fn ms_per_generation(&self, duration: Duration) -> f64 {
+        1000.0 * duration.as_secs_f64() / self.generations.max(1) as f64
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            "{{\"setup\": \"{}\", \"width\": {}, \"height\": {}, \"generations\": {}, \
+             \"population\": {}, \"generations_per_second\": {:.1}, \
+             \"cells_per_second\": {:.0}, \"update_ms\": {:.4}, \"draw_ms\": {:.4}, \
+             \"ansi_ms\": {:.4}}}",
+            self.setup.replace('\\', "\\\\").replace('"', "\\\""),
+            self.size.width(),
+            self.size.height(),
+            self.generations,
+            self.population,
+            self.generations_per_second(),
+            self.cells_per_second(),
+            self.ms_per_generation(self.update),
+            self.ms_per_generation(self.draw),
+            self.ms_per_generation(self.ansi),
+        )
+    }
+}
+
+/// Runs every setup at every size and prints the results as a table or as
+/// JSON.
+pub fn run(args: BenchArgs) {
+    let mut results = Vec::new();
+    for setup in &args.setups {
+        for size in &args.sizes {
+            if !args.json {
+                eprintln!("{} at {}x{}", setup.name, size.width(), size.height());
+            }
+            results.push(run_setup(&args, setup, size));
+        }
+    }
+
+    if args.json {
+        println!("{}", to_json(&results));
+    } else {
+        print!("{}", to_table(&results));
+    }
+}
+
+fn run_setup(args: &BenchArgs, setup: &BenchSetup, size: &Point) -> BenchResult {
+    let state_args = Args {
+        cell_setup: setup.cell_setup.clone(),
+        color_mode: ColorMode::Truecolor,
+        render_mode: args.render_mode,
+        seed: Some(args.seed),
+        ..Args::default()
+    };
+    let mut renderer = Renderer::new(&state_args);
+    renderer.canvas.resize_offscreen(size);
+    let mut state = State::from(state_args);
+    state.resize(size);
+
+    let mut result = BenchResult {
+        ansi: Duration::ZERO,
+        draw: Duration::ZERO,
+        generations: args.generations,
+        population: 0,
+        setup: setup.name.clone(),
+        size: size.clone(),
+        update: Duration::ZERO,
+    };
+
+    for _ in 0..args.generations {
+        let start = Instant::now();
+        state.world.update();
+        result.update += start.elapsed();
+
+        let start = Instant::now();
+        renderer.canvas.clear();
+        renderer.draw_world(&state);
+        result.draw += start.elapsed();
+
+        let start = Instant::now();
+        renderer.canvas.render();
+        result.ansi += start.elapsed();
+    }
+
+    result.population = state.world.statistics.population;
+    result
+}
+
+fn to_table(results: &[BenchResult]) -> String {
+    let mut table = format!(
+        "{:<12} {:>9} {:>6} {:>11} {:>13} {:>10} {:>10} {:>10}\n",
+        "setup", "size", "gens", "gens/s", "cells/s", "update ms", "draw ms", "ansi ms"
+    );
+
+    for result in results {
+        table.push_str(&format!(
+            "{:<12} {:>9} {:>6} {:>11.1} {:>13.0} {:>10.4} {:>10.4} {:>10.4}\n",
+            result.setup,
+            format!("{}x{}", result.size.width(), result.size.height()),
+            result.generations,
+            result.generations_per_second(),
+            result.cells_per_second(),
+            result.ms_per_generation(result.update),
+            result.ms_per_generation(result.draw),
+            result.ms_per_generation(result.ansi),
+        ));
+    }
+
+    table
+}
+
+fn to_json(results: &[BenchResult]) -> String {
+    let results: Vec<String> = results
+        .iter()
+        .map(|result| format!("    {}", result.to_json()))
+        .collect();
+
+    format!(
+        "{{\n  \"version\": \"{}\",\n  \"results\": [\n{}\n  ]\n}}",
+        env!("CARGO_PKG_VERSION"),
+        results.join(",\n")
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::renderer::canvas::RenderMode;
+
+    #[test]
+    fn it_measures_every_generation() {
+        let args = BenchArgs {
+            generations: 3,
+            json: true,
+            render_mode: RenderMode::Braille,
+            seed: 0,
+            setups: vec![],
+            sizes: vec![],
+        };
+        let result = run_setup(
+            &args,
+            &BenchSetup::parse("acorn").unwrap(),
+            &Point::new(20, 10),
+        );
+
+        assert_eq!(result.generations, 3);
+        assert!(result.population > 0);
+        assert!(result.to_json().starts_with(
+            "{\"setup\": \"acorn\", \"width\": 20, \"height\": 10, \"generations\": 3,"
+        ));
+        assert!(to_json(&[result]).contains("\"results\": [\n    {\"setup\""));
+    }
+}
@@ -1,5 +1,14 @@
 pub mod args;
+pub mod bench_setup;
 pub mod color;
+pub mod config;
+pub mod gradient;
+pub mod key_bindings;
+pub mod point_hash;
 pub mod rainbow;
+pub mod size;
+pub mod theme;
 
-pub const DEBUG_INFO_PAGE_TOTAL: i32 = 1;
+/// The rate at which the terminal emits elapse events, frames are scheduled on
+/// top of it so that the frames per second can be changed at runtime.
+pub const TICKS_PER_SECOND: u16 = 100;
@@ -1,19 +1,65 @@
+use crate::common::bench_setup::BenchSetup;
+use crate::common::config;
+use crate::common::config::default_config_file;
+use crate::common::gradient::Gradient;
+use crate::common::key_bindings::KeyBindings;
+use crate::common::rainbow::RainbowMode;
+use crate::common::size::parse_size;
+use crate::common::theme::Theme;
+use crate::common::TICKS_PER_SECOND;
+use crate::export::ExportFormat;
+use crate::renderer::canvas::ascii::parse_cell_chars;
+use crate::renderer::canvas::braille::BrailleColoring;
+use crate::renderer::canvas::color_mode::ColorMode;
+use crate::renderer::canvas::RenderMode;
+use crate::state::cell_image::PatternFormat;
 use crate::state::cell_setup::CellSetup;
 use crate::state::rules::Rules;
+use clap::ArgMatches;
+use clap::Command;
+use clap::CommandFactory;
+use clap::FromArgMatches;
 use clap::Parser;
+use clap::ValueSource;
+use nanorand::Rng;
+use nanorand::WyRand;
+use std::env;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process;
+use std::sync::LazyLock;
+use term2d::model::point::Point;
 use term2d::model::rgba::Rgba;
 
 use super::color::RgbaParser;
 
-/// Simulates game of life like cellular automatons in your terminal.
-/// Keyboard controls:
-///   p           - pause time and enable drawing
-///   space       - toggle cell life in pause/drawing mode
-///   d           - show debug info
-///   q or ctrl-c - quit
+/// The description of the command followed by the keyboard controls, which
+/// are generated from the default key bindings.
+static ABOUT: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "Simulates game of life like cellular automatons in your terminal.\n{}",
+        KeyBindings::default().help().trim_end()
+    )
+});
+
 #[derive(Clone, Debug, Parser)]
-#[clap(author, version, verbatim_doc_comment)]
+#[clap(author, version, about = ABOUT.as_str())]
 pub struct Args {
+    /// Set the gradient for coloring living cells by their age in generations,
+    /// as comma separated stops of an age and a color
+    #[clap(
+        long,
+        value_parser = Gradient::parse,
+        default_value = "0:#FFFFFF,4:#FFEE66,32:#EE8822,256:#CC3355,2048:#5533AA",
+        global = true
+    )]
+    pub age_gradient: Gradient,
+
+    /// Set how the two colors of a character are chosen in braille render
+    /// mode
+    #[clap(long, value_enum, default_value = "majority")]
+    pub braille_coloring: BrailleColoring,
+
     /// Load a world with a predefined cell setup.
     /// Recognized values:
     ///   acorn       - a classic long living minimal configuration
@@ -35,14 +81,48 @@ pub struct Args {
     )]
     pub cell_setup: CellSetup,
 
+    /// Set the characters of living cells in ascii render mode and format,
+    /// two characters like '[]' make square looking cells
+    #[clap(long, value_parser = parse_cell_chars, default_value = "#")]
+    pub char_alive: String,
+
+    /// Set the characters of dead cells in ascii render mode and format
+    #[clap(long, value_parser = parse_cell_chars, default_value = ".")]
+    pub char_dead: String,
+
+    /// Print a census of the objects instead of the pattern in headless mode
+    #[clap(long, value_parser, default_value_t = false)]
+    pub census: bool,
+
+    /// Color living cells by their age instead of the alive or rainbow color
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub color_by_age: bool,
+
     /// Set the initial background color for living cells
-    #[clap(long, value_parser = RgbaParser::parse, default_value = "#EE8822")]
+    #[clap(long, value_parser = RgbaParser::parse, default_value = "#EE8822", global = true)]
     pub color_bg_alive: Rgba,
 
     /// Set the initial background color for dead cells
-    #[clap(long, value_parser = RgbaParser::parse, default_value = "#113011")]
+    #[clap(long, value_parser = RgbaParser::parse, default_value = "#113011", global = true)]
     pub color_bg_dead: Rgba,
 
+    /// Set the colors the terminal can show, all colors are quantized to
+    /// this palette, auto detects it from the COLORTERM and TERM variables
+    #[clap(long, value_enum, default_value = "auto")]
+    pub color_mode: ColorMode,
+
+    #[clap(subcommand)]
+    pub command: Option<Subcommand>,
+
+    /// Read options from this config file instead of
+    /// ~/.config/termgol/config.toml. Options on the command line take
+    /// precedence over TERMGOL_<OPTION> environment variables, which take
+    /// precedence over the config file. A flag which is true in the config
+    /// file is turned off by setting its variable to false, the command line
+    /// can only turn flags on
+    #[clap(long, value_parser)]
+    pub config: Option<PathBuf>,
+
     /// Set the initial delay in milliseconds before the life starts evolving
     #[clap(short, long, value_parser, default_value_t = 1000)]
     pub delay: u64,
@@ -58,22 +138,122 @@ pub struct Args {
         long,
         value_parser,
         default_value_t = 140,
-        verbatim_doc_comment
+        verbatim_doc_comment,
+        global = true
     )]
     pub fading_speed: i32,
 
-    /// Set the frames per second
-    #[clap(short, long, value_parser, default_value_t = 10)]
+    /// Set the number of generations computed per frame in fast forward mode
+    #[clap(long, value_parser, default_value_t = 10)]
+    pub fast_forward: u32,
+
+    /// Set the format of the pattern printed in headless mode
+    #[clap(long, value_enum, default_value = "plaintext")]
+    pub format: PatternFormat,
+
+    /// Set the frames per second, at most 100 which is the rate at which
+    /// frames are scheduled, use fast forward to compute more generations
+    #[clap(
+        short,
+        long,
+        value_parser = clap::value_parser!(u16).range(1..=TICKS_PER_SECOND as i64),
+        default_value_t = 10
+    )]
     pub frames_per_second: u16,
 
+    /// Set the number of generations computed in headless mode
+    #[clap(short, long, value_parser, default_value_t = 0)]
+    pub generations: u64,
+
+    /// Print the heatmap of the cell activity as comma separated rows instead
+    /// of the pattern in headless mode
+    #[clap(long, value_parser, default_value_t = false, conflicts_with = "census")]
+    pub heatmap: bool,
+
+    /// Run without a terminal ui and print the resulting pattern to stdout
+    #[clap(long, value_parser, default_value_t = false)]
+    pub headless: bool,
+
+    /// Set the height of the toroidal world in headless mode, the universe is
+    /// unbounded if width and height are omitted
+    #[clap(
+        long,
+        value_parser = clap::value_parser!(u16).range(1..),
+        requires = "width"
+    )]
+    pub height: Option<u16>,
+
+    /// Rebind keys as comma separated action=key pairs, e.g.
+    /// 'pause=P,cursor-left=a,cursor-left=left', keys are characters, ctrl-x,
+    /// alt-x, f1 to f12 or one of backspace, comma, delete, down, end, enter,
+    /// esc, home, left, pagedown, pageup, right, space and tab
+    #[clap(long, value_parser = KeyBindings::parse, default_value = "")]
+    pub keys: KeyBindings,
+
     /// Start paused so that you can edit the world
     #[clap(short, long, value_parser, default_value_t = false)]
     pub paused: bool,
 
-    /// Start paused so that you can edit the world
+    /// Print the effective configuration of the config file, the environment
+    /// and the command line as a config file and exit
     #[clap(long, value_parser, default_value_t = false)]
+    pub print_config: bool,
+
+    /// Start paused so that you can edit the world
+    #[clap(long, value_parser, default_value_t = false, global = true)]
     pub rainbow: bool,
 
+    /// Set the angle of the rainbow in degrees, clockwise from the x axis,
+    /// linear rainbows follow the screen diagonal if omitted
+    #[clap(long, value_parser, allow_hyphen_values = true, global = true)]
+    pub rainbow_angle: Option<f32>,
+
+    /// Set the colors of the rainbow, either evenly spread like
+    /// '#ff0000,#00ff00' or as stops from 0 to 1 like '0:#ff0000,0.2:#00ff00'
+    #[clap(
+        long,
+        value_parser = Gradient::parse_colors,
+        default_value = "#ff0000,#ffff00,#00ff00,#00ffff,#0000ff,#ff00ff",
+        global = true
+    )]
+    pub rainbow_colors: Gradient,
+
+    /// Animate the rainbow by shifting it by this fraction of its length every
+    /// frame, e.g. 0.01
+    #[clap(
+        long,
+        value_parser,
+        default_value_t = 0.0,
+        allow_hyphen_values = true,
+        global = true
+    )]
+    pub rainbow_drift: f32,
+
+    /// Set the shape of the rainbow
+    #[clap(long, value_enum, default_value = "linear", global = true)]
+    pub rainbow_mode: RainbowMode,
+
+    /// Set how cells are drawn: halfblock shows 1x2 cells per character,
+    /// braille 2x4 cells per character and ascii one cell per plain character
+    /// without colors
+    #[clap(long, value_enum, default_value = "halfblock")]
+    pub render_mode: RenderMode,
+
+    /// Record the session to an asciicast v2 file, which plays back with
+    /// colors in any asciinema player
+    #[clap(long, value_parser)]
+    pub record: Option<PathBuf>,
+
+    /// Only record the frames in which the world advanced, at the frame rate,
+    /// without pauses and edits
+    #[clap(long, value_parser, default_value_t = false, requires = "record")]
+    pub record_simulation_only: bool,
+
+    /// Reset the screen saver early as soon as the world becomes static or
+    /// periodic
+    #[clap(long, value_parser, default_value_t = false)]
+    pub reset_when_stable: bool,
+
     /// Set the birth and survival rules, defaults to conway's game of life
     /// rules. For the rule notation see:
     /// https://en.wikipedia.org/wiki/Life-like_cellular_automaton#Notation_for_rules
@@ -87,10 +267,164 @@ pub struct Args {
     )]
     pub rules: Rules,
 
+    /// Compute the world up to the specified generation before showing it
+    /// paused
+    #[clap(short, long, value_parser)]
+    pub until: Option<u64>,
+
+    /// Set the seed for random cell setups and screen saver soups, a random
+    /// seed is chosen if omitted
+    #[clap(long, value_parser)]
+    pub seed: Option<u64>,
+
+    /// Set the colors of living and dead cells, the cursor, text and the
+    /// rainbow, unless they are set explicitly. Either one of the built-in
+    /// themes default, matrix, solarized, amber and paper, the name of a file
+    /// in ~/.config/termgol/themes/<name>.toml or the path of a theme file
+    #[clap(long, value_parser = Theme::parse, default_value = "default", global = true)]
+    pub theme: Theme,
+
     /// Start in screen saver mode: sets up a new random soup after the specified
     /// number of elapsed frames.
     #[clap(short, long, value_parser)]
     pub screen_saver: Option<u32>,
+
+    /// Show the status bar with generation, population, rules, speed, mode
+    /// and topology, the world shrinks to make room for it
+    #[clap(long, value_parser, default_value_t = false)]
+    pub status_bar: bool,
+
+    /// Add a caption with the rules and the generation to SVG exports
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub svg_caption: bool,
+
+    /// Add grid lines between the cells to SVG exports
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub svg_grid: bool,
+
+    /// Set the width of the toroidal world in headless mode, the universe is
+    /// unbounded if width and height are omitted
+    #[clap(
+        long,
+        value_parser = clap::value_parser!(u16).range(1..),
+        requires = "height"
+    )]
+    pub width: Option<u16>,
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+pub enum Subcommand {
+    /// Measure how fast setups are computed and rendered at fixed sizes,
+    /// without a terminal
+    Bench(BenchArgs),
+
+    /// Export the generations of a cell setup or pattern file as GIF
+    /// animation or the last of them as PNG or SVG image, in the colors of the
+    /// color, fading, rainbow and theme options
+    Export(ExportArgs),
+
+    /// Search rare objects in the ash of random 16x16 soups
+    Search(SearchArgs),
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct BenchArgs {
+    /// Set the number of generations computed per setup and size
+    #[clap(short, long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 500)]
+    pub generations: u64,
+
+    /// Print the results as JSON, e.g. to compare them across releases
+    #[clap(long, value_parser, default_value_t = false)]
+    pub json: bool,
+
+    /// Set how the world is rendered
+    #[clap(long, value_enum, default_value = "halfblock")]
+    pub render_mode: RenderMode,
+
+    /// Set the seed of random setups
+    #[clap(long, value_parser, default_value_t = 0)]
+    pub seed: u64,
+
+    /// Set the cell setups as comma separated names like for --cell-setup
+    #[clap(
+        long,
+        value_parser = BenchSetup::parse,
+        use_value_delimiter = true,
+        default_value = "random30,soup100,acorn"
+    )]
+    pub setups: Vec<BenchSetup>,
+
+    /// Set the sizes of the world in cells as comma separated WIDTHxHEIGHT
+    #[clap(
+        long,
+        value_parser = parse_size,
+        use_value_delimiter = true,
+        default_value = "100x50,400x200"
+    )]
+    pub sizes: Vec<Point>,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct ExportArgs {
+    /// Set the size of a cell in pixels
+    #[clap(long, value_parser = clap::value_parser!(u16).range(1..), default_value_t = 4)]
+    pub cell_size: u16,
+
+    /// Set the cell setup like for --cell-setup, defaults to its value
+    #[clap(short, long, value_parser = CellSetup::parse)]
+    pub cell_setup: Option<CellSetup>,
+
+    /// Set the format of the exported file
+    #[clap(long, value_enum, default_value = "gif")]
+    pub format: ExportFormat,
+
+    /// Set the frames per second of the animation, defaults to the value of
+    /// --frames-per-second
+    #[clap(short, long, value_parser = clap::value_parser!(u16).range(1..))]
+    pub frames_per_second: Option<u16>,
+
+    /// Set the number of generations computed after the cell setup, defaults
+    /// to 100 for GIF animations and 0 for PNG and SVG images
+    #[clap(short, long, value_parser)]
+    pub generations: Option<u64>,
+
+    /// Set the size of the toroidal world in cells as WIDTHxHEIGHT
+    #[clap(long, value_parser = parse_size, default_value = "100x100")]
+    pub size: Point,
+
+    /// Set the path of the exported file
+    #[clap(value_parser)]
+    pub output: PathBuf,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct SearchArgs {
+    /// Set the maximum number of generations a soup is computed before its
+    /// census is taken
+    #[clap(short, long, value_parser, default_value_t = 10000)]
+    pub generations: u64,
+
+    /// Set the directory for the progress, the census and the rare soups, an
+    /// interrupted search continues where it stopped
+    #[clap(long, value_parser, default_value = "search-results")]
+    pub results: PathBuf,
+
+    /// Set the birth and survival rules
+    #[clap(short, long, value_parser = Rules::parse, default_value = "B3/S23")]
+    pub rules: Rules,
+
+    /// Set the seed of the first soup, the following soups use the next seeds
+    #[clap(long, value_parser)]
+    pub seed: Option<u64>,
+
+    /// Stop after the specified total number of soups, search until
+    /// interrupted if omitted
+    #[clap(long, value_parser)]
+    pub soups: Option<u64>,
+
+    /// Set the number of threads, defaults to the available parallelism
+    #[clap(short, long, value_parser)]
+    pub threads: Option<usize>,
 }
 
 // Ideally we would set the Args default values in its Default impl,
@@ -99,6 +433,217 @@ pub struct Args {
 // way around.
 impl Default for Args {
     fn default() -> Self {
-        Self::parse_from(Vec::<String>::new().into_iter())
+        Self::parse_from(Vec::<String>::new())
+    }
+}
+
+impl Args {
+    /// Parses the config file, the environment and the command line, colors
+    /// which are not set explicitly are taken from the theme.
+    pub fn parse_themed() -> Self {
+        let mut command = Self::command().args_override_self(true);
+        command.build();
+
+        let matches = Self::layered_matches(
+            &command,
+            env::args_os().collect(),
+            |variable| env::var(variable).ok(),
+            default_config_file(),
+        )
+        .unwrap_or_else(|error| error.exit());
+
+        if matches.get_one::<bool>("print-config") == Some(&true) {
+            print!("{}", config::to_toml(&command, &matches));
+            process::exit(0);
+        }
+
+        Self::from_matches_themed(&matches)
+    }
+
+    /// Puts the options of the config file and the environment in front of
+    /// the command line, so that later options override earlier ones.
+    fn layered_matches(
+        command: &Command,
+        command_line: Vec<OsString>,
+        vars: impl Fn(&str) -> Option<String>,
+        default_config_file: Option<PathBuf>,
+    ) -> Result<ArgMatches, clap::Error> {
+        let config_file = command
+            .clone()
+            .ignore_errors(true)
+            .try_get_matches_from(&command_line)
+            .ok()
+            .and_then(|matches| matches.get_one::<PathBuf>("config").cloned())
+            .or_else(|| default_config_file.filter(|path| path.exists()));
+
+        let mut config_args = Vec::new();
+        if let Some(path) = config_file {
+            config_args.extend(config::file_args(command, &path)?);
+        }
+        config_args.extend(config::env_args(command, vars)?);
+
+        let mut layered = command_line[..1].to_vec();
+        layered.extend(config::to_command_line(&config_args));
+        layered.extend_from_slice(&command_line[1..]);
+
+        command.clone().try_get_matches_from(layered)
+    }
+
+    fn from_matches_themed(matches: &ArgMatches) -> Self {
+        let mut args = Self::from_arg_matches(matches).unwrap_or_else(|error| error.exit());
+        let is_default = |id: &str| matches.value_source(id) == Some(ValueSource::DefaultValue);
+
+        if is_default("color-bg-alive") {
+            args.color_bg_alive = args.theme.alive.clone();
+        }
+        if is_default("color-bg-dead") {
+            args.color_bg_dead = args.theme.dead.clone();
+        }
+        if is_default("rainbow-colors") {
+            args.rainbow_colors = args.theme.rainbow.clone();
+        }
+
+        args
+    }
+
+    /// Chooses a random seed if none was specified, so that the seed in use is
+    /// always known and can be reported.
+    pub fn resolve_seed(&mut self) -> u64 {
+        *self.seed.get_or_insert_with(|| WyRand::new().generate())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::from_matches_themed(&Args::command().get_matches_from(args))
+    }
+
+    #[test]
+    fn it_takes_colors_from_the_theme_unless_set_explicitly() {
+        let matrix = Theme::parse("matrix").unwrap();
+
+        let args = parse(&["termgol", "--theme", "matrix"]);
+        assert_eq!(args.color_bg_alive, matrix.alive);
+        assert_eq!(args.color_bg_dead, matrix.dead);
+        assert_eq!(args.rainbow_colors, matrix.rainbow);
+
+        let args = parse(&["termgol", "--theme", "matrix", "--color-bg-dead", "#0000ff"]);
+        assert_eq!(args.color_bg_alive, matrix.alive);
+        assert_eq!(args.color_bg_dead, Rgba::blue());
+    }
+
+    #[test]
+    fn it_limits_the_frames_per_second_to_the_ticks() {
+        assert_eq!(parse(&["termgol", "-f", "100"]).frames_per_second, 100);
+        assert!(Args::try_parse_from(["termgol", "-f", "101"]).is_err());
+        assert!(Args::try_parse_from(["termgol", "-f", "0"]).is_err());
+    }
+
+    #[test]
+    fn it_rejects_empty_toroidal_worlds() {
+        assert!(Args::try_parse_from(["termgol", "--width=-3", "--height=5"]).is_err());
+        assert!(Args::try_parse_from(["termgol", "--width=0", "--height=0"]).is_err());
+        assert_eq!(
+            parse(&["termgol", "--width=3", "--height=5"]).width,
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn it_benchmarks_at_least_one_generation() {
+        assert!(Args::try_parse_from(["termgol", "bench", "--generations", "0"]).is_err());
+        assert!(Args::try_parse_from(["termgol", "bench", "--generations", "1"]).is_ok());
+    }
+
+    #[test]
+    fn it_accepts_svg_options_after_export() {
+        let args = parse(&[
+            "termgol",
+            "export",
+            "--svg-grid",
+            "--svg-caption",
+            "out.svg",
+        ]);
+        let Some(Subcommand::Export(export_args)) = args.command else {
+            panic!("not an export");
+        };
+        assert_eq!(export_args.output, PathBuf::from("out.svg"));
+        assert!(args.svg_caption && args.svg_grid);
+    }
+
+    #[test]
+    fn it_accepts_color_options_after_export() {
+        let args = parse(&[
+            "termgol",
+            "export",
+            "--rainbow",
+            "--theme",
+            "matrix",
+            "out.gif",
+        ]);
+        assert!(args.rainbow);
+        assert_eq!(args.color_bg_alive, Theme::parse("matrix").unwrap().alive);
+    }
+
+    #[test]
+    fn it_keeps_the_help_file_up_to_date() {
+        let mut help = Vec::new();
+        Args::command().write_help(&mut help).unwrap();
+
+        assert_eq!(
+            String::from_utf8(help).unwrap(),
+            include_str!("../../HELP"),
+            "regenerate HELP with: cargo run -- -h > HELP"
+        );
+    }
+
+    #[test]
+    fn it_layers_command_line_over_environment_over_config_file() {
+        let path = env::temp_dir().join(format!("termgol-test-config-{}.toml", process::id()));
+        fs::write(
+            &path,
+            "delay = 5\nfading_speed = 10\nrules = \"B36/S23\"\npaused = true\n",
+        )
+        .unwrap();
+        let mut command = Args::command().args_override_self(true);
+        command.build();
+        let vars = |variable: &str| (variable == "TERMGOL_FADING_SPEED").then(|| "20".to_string());
+        let command_line = ["termgol", "--rules", "B3/S23"]
+            .map(OsString::from)
+            .to_vec();
+
+        let matches =
+            Args::layered_matches(&command, command_line, vars, Some(path.clone())).unwrap();
+        let args = Args::from_matches_themed(&matches);
+        assert_eq!(args.delay, 5);
+        assert_eq!(args.fading_speed, 20);
+        assert_eq!(args.rules, Rules::parse("B3/S23").unwrap());
+        assert!(args.paused);
+
+        let vars = |variable: &str| (variable == "TERMGOL_PAUSED").then(|| "false".to_string());
+        let command_line = ["termgol"].map(OsString::from).to_vec();
+        let matches =
+            Args::layered_matches(&command, command_line, vars, Some(path.clone())).unwrap();
+        assert!(!Args::from_matches_themed(&matches).paused);
+
+        let command_line = ["termgol", "--paused"].map(OsString::from).to_vec();
+        let matches =
+            Args::layered_matches(&command, command_line, vars, Some(path.clone())).unwrap();
+        assert!(Args::from_matches_themed(&matches).paused);
+
+        fs::write(&path, "delay = \"soon\"\n").unwrap();
+        let command_line = ["termgol"].map(OsString::from).to_vec();
+        let error = Args::layered_matches(&command, command_line, |_| None, Some(path.clone()))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'delay'"));
+        assert!(error.contains(&path.display().to_string()));
+
+        fs::remove_file(path).unwrap();
     }
 }
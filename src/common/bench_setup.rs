@@ -0,0 +1,19 @@
+use clap::Error;
+
+use crate::state::cell_setup::CellSetup;
+
+/// A cell setup and the name it was given on the command line.
+#[derive(Clone, Debug)]
+pub struct BenchSetup {
+    pub cell_setup: CellSetup,
+    pub name: String,
+}
+
+impl BenchSetup {
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        Ok(Self {
+            cell_setup: CellSetup::parse(s)?,
+            name: s.to_string(),
+        })
+    }
+}
@@ -22,6 +22,10 @@ impl RgbaParser {
 
         Ok(term2d::model::rgba::Rgba { r, g, b, a: 255 })
     }
+
+    pub fn format(rgba: &term2d::model::rgba::Rgba) -> String {
+        format!("#{:02x}{:02x}{:02x}", rgba.r, rgba.g, rgba.b)
+    }
 }
 
 #[cfg(test)]
@@ -48,6 +52,12 @@ mod test {
         assert_eq!(a, 255);
     }
 
+    #[test]
+    fn it_formats_colors_as_hex_codes() {
+        let rgba = RgbaParser::parse("#EE8822").unwrap();
+        assert_eq!(RgbaParser::format(&rgba), "#ee8822");
+    }
+
     #[test]
     fn it_fails_when_the_rgb_hex_code_is_invalid() {
         let err = RgbaParser::parse("nonsense");
@@ -0,0 +1,329 @@
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use clap::Arg;
+use clap::ArgAction;
+use clap::ArgMatches;
+use clap::Command;
+use clap::Error;
+use clap::ErrorKind;
+use clap::ValueSource;
+
+use super::theme::config_directory;
+
+const CONFIG_FILE: &str = "config.toml";
+const ENV_PREFIX: &str = "TERMGOL_";
+
+/// Options which only make sense on the command line.
+const COMMAND_LINE_ONLY: [&str; 4] = ["config", "help", "print-config", "version"];
+
+/// The config file in the directory of the user configuration.
+pub fn default_config_file() -> Option<PathBuf> {
+    config_directory().map(|directory| directory.join(CONFIG_FILE))
+}
+
+/// The environment variable which sets an option, e.g. `TERMGOL_FADING_SPEED`
+/// for `--fading-speed`.
+pub fn env_variable(long: &str) -> String {
+    format!("{ENV_PREFIX}{}", long.to_uppercase().replace('-', "_"))
+}
+
+fn configurable_args<'a, 'help>(
+    command: &'a Command<'help>,
+) -> impl Iterator<Item = (&'a Arg<'help>, &'help str)> {
+    command.get_arguments().filter_map(|arg| {
+        let long = arg.get_long()?;
+        (!COMMAND_LINE_ONLY.contains(&long)).then_some((arg, long))
+    })
+}
+
+fn is_flag(arg: &Arg) -> bool {
+    matches!(arg.get_action(), ArgAction::SetTrue)
+}
+
+/// An option set by the config file or an environment variable. Flags are
+/// kept as values, so that a later layer can turn them off again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigArg {
+    Flag(String, bool),
+    Option(OsString),
+}
+
+/// Converts the options of the layers into command line arguments. A flag is
+/// set if the last layer which mentions it sets it to `true`.
+pub fn to_command_line(layers: &[ConfigArg]) -> Vec<OsString> {
+    let mut command_line = Vec::new();
+    let mut flags: Vec<(&str, bool)> = Vec::new();
+
+    for layer in layers {
+        match layer {
+            ConfigArg::Flag(long, value) => {
+                flags.retain(|(l, _)| l != long);
+                flags.push((long, *value));
+            }
+            ConfigArg::Option(arg) => command_line.push(arg.clone()),
+        }
+    }
+    command_line.extend(
+        flags
+            .into_iter()
+            .filter(|(_, value)| *value)
+            .map(|(long, _)| OsString::from(format!("--{long}"))),
+    );
+
+    command_line
+}
+
+/// Converts the value of an option into a config argument, after checking
+/// that the command accepts it.
+fn to_config_arg(
+    command: &Command,
+    arg: &Arg,
+    long: &str,
+    value: &str,
+    source: &str,
+) -> Result<ConfigArg, Error> {
+    let config_arg = match (is_flag(arg), value) {
+        (true, "true") => ConfigArg::Flag(long.to_string(), true),
+        (true, "false") => ConfigArg::Flag(long.to_string(), false),
+        (true, _) => {
+            return Err(invalid(
+                source,
+                &format!("expected true or false, got '{value}'"),
+            ));
+        }
+        (false, _) => ConfigArg::Option(format!("--{long}={value}").into()),
+    };
+
+    // Missing requirements and conflicts are reported once all options are
+    // combined.
+    let mut check = vec![OsString::from(command.get_name())];
+    check.extend(to_command_line(std::slice::from_ref(&config_arg)));
+    match command.clone().try_get_matches_from(check) {
+        Err(error)
+            if !matches!(
+                error.kind(),
+                ErrorKind::MissingRequiredArgument | ErrorKind::ArgumentConflict
+            ) =>
+        {
+            let message = error.to_string();
+            let first_line = message.lines().next().unwrap_or_default();
+            let first_line = first_line.trim_start_matches("error: ");
+            Err(invalid(source, &first_line.replace(": error: ", ": ")))
+        }
+        _ => Ok(config_arg),
+    }
+}
+
+fn invalid(source: &str, message: &str) -> Error {
+    Command::new(format!("fix {source}")).error(
+        ErrorKind::InvalidValue,
+        format!("invalid value of {source}: {message}"),
+    )
+}
+
+/// Reads the options of a config file.
+pub fn file_args(command: &Command, path: &Path) -> Result<Vec<ConfigArg>, Error> {
+    let content = fs::read_to_string(path).map_err(|error| {
+        Command::new("set --config to the path of a readable file").error(
+            ErrorKind::Io,
+            format!("cannot read config {}: {error}", path.display()),
+        )
+    })?;
+
+    let mut config_args = Vec::new();
+    for entry in parse_entries(&content) {
+        let (line, key, value) = entry.map_err(|(line, message)| {
+            Command::new(format!("fix line {line} in {}", path.display())).error(
+                ErrorKind::InvalidValue,
+                format!("invalid line {line} in {}: {message}", path.display()),
+            )
+        })?;
+
+        let source = format!("key '{key}' in {}", path.display());
+        let long = key.replace('_', "-");
+        let Some((arg, long)) = configurable_args(command).find(|(_, l)| *l == long) else {
+            return Err(
+                Command::new(format!("fix line {line} in {}", path.display()))
+                    .error(ErrorKind::UnknownArgument, format!("unknown {source}")),
+            );
+        };
+
+        config_args.push(to_config_arg(command, arg, long, &value, &source)?);
+    }
+
+    Ok(config_args)
+}
+
+/// Reads the options set by environment variables.
+pub fn env_args(
+    command: &Command,
+    vars: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<ConfigArg>, Error> {
+    let mut config_args = Vec::new();
+    for (arg, long) in configurable_args(command) {
+        let variable = env_variable(long);
+        if let Some(value) = vars(&variable) {
+            let source = format!("environment variable {variable}");
+            config_args.push(to_config_arg(command, arg, long, &value, &source)?);
+        }
+    }
+
+    Ok(config_args)
+}
+
+/// Formats the effective value of every option as a config file, default
+/// values are commented out so that they do not override the theme.
+pub fn to_toml(command: &Command, matches: &ArgMatches) -> String {
+    let mut toml = String::new();
+
+    for (arg, long) in configurable_args(command) {
+        let key = long.replace('-', "_");
+        let comment = match matches.value_source(arg.get_id()) {
+            Some(ValueSource::DefaultValue) | None => "# ",
+            _ => "",
+        };
+        let value = if is_flag(arg) {
+            matches.get_one::<bool>(arg.get_id()).map(bool::to_string)
+        } else {
+            matches
+                .get_raw(arg.get_id())
+                .and_then(|mut values| values.next_back())
+                .map(|value| format_value(&value.to_string_lossy()))
+        };
+
+        match value {
+            Some(value) => writeln!(toml, "{comment}{key} = {value}").unwrap(),
+            None => writeln!(toml, "# {key} =").unwrap(),
+        }
+    }
+
+    toml
+}
+
+/// Numbers and booleans are written bare, everything else as a string.
+fn format_value(value: &str) -> String {
+    if value.parse::<f64>().is_ok() || value == "true" || value == "false" {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+pub type Entry = Result<(usize, String, String), (usize, String)>;
+
+/// Parses the `key = value` lines of a flat TOML file, values are strings,
+/// numbers or booleans.
+pub fn parse_entries(content: &str) -> Vec<Entry> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line, text)| {
+            let (key, value) = text
+                .split_once('=')
+                .ok_or((line, "expected key = value".to_string()))?;
+            let value = parse_value(value.trim()).map_err(|message| (line, message))?;
+            Ok((line, key.trim().trim_matches('"').to_string(), value))
+        })
+        .collect()
+}
+
+fn parse_value(value: &str) -> Result<String, String> {
+    let (parsed, rest) = if let Some(quoted) = value.strip_prefix('"') {
+        let mut parsed = String::new();
+        let mut chars = quoted.char_indices();
+        loop {
+            match chars.next() {
+                Some((_, '\\')) => match chars.next() {
+                    Some((_, 'n')) => parsed.push('\n'),
+                    Some((_, 't')) => parsed.push('\t'),
+                    Some((_, c)) => parsed.push(c),
+                    None => return Err("unterminated string".to_string()),
+                },
+                Some((i, '"')) => break (parsed, &quoted[i + 1..]),
+                Some((_, c)) => parsed.push(c),
+                None => return Err("unterminated string".to_string()),
+            }
+        }
+    } else if let Some(literal) = value.strip_prefix('\'') {
+        let end = literal
+            .find('\'')
+            .ok_or_else(|| "unterminated string".to_string())?;
+        (literal[..end].to_string(), &literal[end + 1..])
+    } else {
+        let (bare, rest) = value
+            .split_once('#')
+            .map_or((value, ""), |(bare, comment)| {
+                (bare, &value[value.len() - comment.len() - 1..])
+            });
+        let bare = bare.trim();
+        if bare.parse::<f64>().is_err() && bare != "true" && bare != "false" {
+            return Err(format!(
+                "expected a string, number or boolean, got '{bare}'"
+            ));
+        }
+        (bare.to_string(), rest)
+    };
+
+    let rest = rest.trim();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return Err(format!("unexpected '{rest}' after the value"));
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_flat_toml() {
+        let entries = parse_entries(
+            "# termgol\nfps = 30 # smooth\ncolor_bg_alive = \"#ff0000\"\n\nrainbow = true\nchar_alive = '[]'\n",
+        );
+        assert_eq!(
+            entries,
+            vec![
+                Ok((2, "fps".to_string(), "30".to_string())),
+                Ok((3, "color_bg_alive".to_string(), "#ff0000".to_string())),
+                Ok((5, "rainbow".to_string(), "true".to_string())),
+                Ok((6, "char_alive".to_string(), "[]".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_fails_on_invalid_toml() {
+        assert!(parse_entries("fps")[0].is_err());
+        assert!(parse_entries("fps = fast")[0].is_err());
+        assert!(parse_entries("theme = \"matrix")[0].is_err());
+        assert!(parse_entries("theme = \"matrix\" x")[0].is_err());
+    }
+
+    #[test]
+    fn it_lets_the_last_layer_decide_flags() {
+        let layers = [
+            ConfigArg::Flag("paused".to_string(), true),
+            ConfigArg::Option("--delay=5".into()),
+            ConfigArg::Flag("rainbow".to_string(), true),
+            ConfigArg::Flag("paused".to_string(), false),
+        ];
+        assert_eq!(
+            to_command_line(&layers),
+            vec![OsString::from("--delay=5"), OsString::from("--rainbow")]
+        );
+    }
+
+    #[test]
+    fn it_formats_values() {
+        assert_eq!(format_value("10"), "10");
+        assert_eq!(format_value("B3/S23"), "\"B3/S23\"");
+        assert_eq!(format_value("a \"b\""), "\"a \\\"b\\\"\"");
+    }
+}
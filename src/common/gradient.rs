@@ -0,0 +1,146 @@
+use std::fmt::Display;
+
+use clap::Command;
+use clap::Error;
+use clap::ErrorKind;
+use term2d::model::rgba::Rgba;
+
+use super::color::RgbaParser;
+
+/// Interpolates linearly between colors at ascending positions, positions
+/// outside of the stops get the color of the nearest stop.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    stops: Vec<(f32, Rgba)>,
+}
+
+impl Gradient {
+    pub fn new(mut stops: Vec<(f32, Rgba)>) -> Self {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops }
+    }
+
+    /// Parses comma separated stops of a position and a color, e.g.
+    /// `0:#ffffff,10:#ee8822`.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let error_map = || {
+            Command::new("set argument to e.g. '0:#ffffff,10:#ee8822'")
+                .error(ErrorKind::InvalidValue, "invalid gradient")
+        };
+
+        let stops = s
+            .split(',')
+            .map(|stop| {
+                let (position, color) = stop.trim().split_once(':').ok_or_else(error_map)?;
+                let position: f32 = position.parse().map_err(|_| error_map())?;
+                let color = RgbaParser::parse(color).map_err(|_| error_map())?;
+                Ok((position, color))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self::new(stops))
+    }
+
+    /// Parses either comma separated colors, which are spread evenly over the
+    /// positions 0 to 1, or stops like `parse`.
+    pub fn parse_colors(s: &str) -> Result<Self, Error> {
+        if s.contains(':') {
+            return Self::parse(s);
+        }
+
+        let colors = s
+            .split(',')
+            .map(|color| RgbaParser::parse(color.trim()))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let last = (colors.len() - 1).max(1) as f32;
+
+        Ok(Self::new(
+            colors
+                .into_iter()
+                .enumerate()
+                .map(|(i, color)| (i as f32 / last, color))
+                .collect(),
+        ))
+    }
+
+    pub fn at(&self, position: f32) -> Rgba {
+        let Some(upper) = self.stops.iter().position(|stop| stop.0 > position) else {
+            return self
+                .stops
+                .last()
+                .map_or(Rgba::default(), |stop| stop.1.clone());
+        };
+        if upper == 0 {
+            return self.stops[0].1.clone();
+        }
+
+        let (p1, color1) = &self.stops[upper - 1];
+        let (p2, color2) = &self.stops[upper];
+        let a = (position - p1) / (p2 - p1);
+        let mix = |c1: u8, c2: u8| ((1.0 - a) * c1 as f32 + a * c2 as f32).round() as u8;
+
+        Rgba {
+            r: mix(color1.r, color2.r),
+            g: mix(color1.g, color2.g),
+            b: mix(color1.b, color2.b),
+            a: 255,
+        }
+    }
+}
+
+impl Display for Gradient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let stops: Vec<String> = self
+            .stops
+            .iter()
+            .map(|(position, color)| format!("{position}:{}", RgbaParser::format(color)))
+            .collect();
+        write!(f, "{}", stops.join(","))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_interpolates_between_stops() {
+        let gradient = Gradient::parse("0:#000000,10:#ff0080").unwrap();
+        assert_eq!(gradient.at(0.0), Rgba::black());
+        assert_eq!(
+            gradient.at(5.0),
+            Rgba {
+                r: 128,
+                g: 0,
+                b: 64,
+                a: 255
+            }
+        );
+    }
+
+    #[test]
+    fn it_clamps_positions_outside_of_the_stops() {
+        let gradient = Gradient::parse("10:#ff0000,0:#00ff00").unwrap();
+        assert_eq!(gradient.at(-5.0), Rgba::green());
+        assert_eq!(gradient.at(100.0), Rgba::red());
+        assert_eq!(gradient.to_string(), "0:#00ff00,10:#ff0000");
+    }
+
+    #[test]
+    fn it_spreads_colors_evenly() {
+        let gradient = Gradient::parse_colors("#ff0000,#00ff00,#0000ff").unwrap();
+        assert_eq!(gradient.to_string(), "0:#ff0000,0.5:#00ff00,1:#0000ff");
+        assert_eq!(
+            Gradient::parse_colors("0:#ff0000,2:#0000ff").unwrap(),
+            Gradient::parse("0:#ff0000,2:#0000ff").unwrap()
+        );
+        assert!(Gradient::parse_colors("#ff0000,red").is_err());
+    }
+
+    #[test]
+    fn it_fails_when_the_gradient_is_invalid() {
+        assert!(Gradient::parse("").is_err());
+        assert!(Gradient::parse("0:#ffffff,10").is_err());
+        assert!(Gradient::parse("x:#ffffff").is_err());
+    }
+}
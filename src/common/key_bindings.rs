@@ -0,0 +1,449 @@
+use clap::Command;
+use clap::Error;
+use clap::ErrorKind;
+use term2d::model::key::Key;
+
+/// Something the user can do with a key press.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Age,
+    BirthsDeaths,
+    Census,
+    Command,
+    CursorDown,
+    CursorLeft,
+    CursorRight,
+    CursorUp,
+    Debug,
+    FadeFaster,
+    FadeSlower,
+    FastForward,
+    Faster,
+    Graph,
+    GraphShorter,
+    GraphTaller,
+    Heatmap,
+    Identify,
+    Keys,
+    LogScale,
+    NextColor,
+    NextRules,
+    Pause,
+    PreviousRules,
+    Quit,
+    Rainbow,
+    Select,
+    Slower,
+    Snapshot,
+    Sparkline,
+    StatusBar,
+    Step,
+    Svg,
+    ToggleCell,
+}
+
+/// Every action with its name, default keys and description, in the order
+/// of the keyboard controls.
+const ACTIONS: [(Action, &str, &[Key], &str); 34] = [
+    (
+        Action::Pause,
+        "pause",
+        &[Key::Char('p')],
+        "pause time and enable drawing",
+    ),
+    (
+        Action::Step,
+        "step",
+        &[Key::Char('n')],
+        "step a single generation in pause/drawing mode",
+    ),
+    (
+        Action::FastForward,
+        "fast-forward",
+        &[Key::Char('f')],
+        "toggle fast forward",
+    ),
+    (
+        Action::ToggleCell,
+        "toggle-cell",
+        &[Key::Char(' ')],
+        "toggle cell life in pause/drawing mode",
+    ),
+    (
+        Action::CursorLeft,
+        "cursor-left",
+        &[Key::Char('h'), Key::Left],
+        "move the cursor left in pause/drawing mode",
+    ),
+    (
+        Action::CursorDown,
+        "cursor-down",
+        &[Key::Char('j'), Key::Down],
+        "move the cursor down in pause/drawing mode",
+    ),
+    (
+        Action::CursorUp,
+        "cursor-up",
+        &[Key::Char('k'), Key::Up],
+        "move the cursor up in pause/drawing mode",
+    ),
+    (
+        Action::CursorRight,
+        "cursor-right",
+        &[Key::Char('l'), Key::Right],
+        "move the cursor right in pause/drawing mode",
+    ),
+    (
+        Action::Select,
+        "select",
+        &[Key::Char('v')],
+        "start or clear a selection in pause/drawing mode",
+    ),
+    (
+        Action::Identify,
+        "identify",
+        &[Key::Char('i')],
+        "identify the selected pattern or the whole world as\nstill life, oscillator or spaceship",
+    ),
+    (
+        Action::NextRules,
+        "next-rules",
+        &[Key::Char('r')],
+        "cycle forward through well known rules",
+    ),
+    (
+        Action::PreviousRules,
+        "previous-rules",
+        &[Key::Char('R')],
+        "cycle backward through well known rules",
+    ),
+    (
+        Action::Faster,
+        "faster",
+        &[Key::Char('+')],
+        "increase the frames per second, up to 100",
+    ),
+    (
+        Action::Slower,
+        "slower",
+        &[Key::Char('-')],
+        "decrease the frames per second",
+    ),
+    (
+        Action::FadeFaster,
+        "fade-faster",
+        &[Key::Char(']')],
+        "increase the fading speed",
+    ),
+    (
+        Action::FadeSlower,
+        "fade-slower",
+        &[Key::Char('[')],
+        "decrease the fading speed",
+    ),
+    (
+        Action::NextColor,
+        "next-color",
+        &[Key::Char('c')],
+        "cycle through colors for living cells",
+    ),
+    (
+        Action::Rainbow,
+        "rainbow",
+        &[Key::Char('C')],
+        "toggle rainbow colors",
+    ),
+    (
+        Action::Age,
+        "age",
+        &[Key::Char('a')],
+        "toggle coloring living cells by their age",
+    ),
+    (
+        Action::Heatmap,
+        "heatmap",
+        &[Key::Char('m')],
+        "toggle the heatmap of the cell activity",
+    ),
+    (
+        Action::Graph,
+        "graph",
+        &[Key::Char('g')],
+        "toggle the population graph",
+    ),
+    (
+        Action::BirthsDeaths,
+        "births-deaths",
+        &[Key::Char('b')],
+        "toggle births and deaths in the population graph",
+    ),
+    (
+        Action::GraphTaller,
+        "graph-taller",
+        &[Key::Char('}')],
+        "increase the height of the population graph",
+    ),
+    (
+        Action::GraphShorter,
+        "graph-shorter",
+        &[Key::Char('{')],
+        "decrease the height of the population graph",
+    ),
+    (
+        Action::LogScale,
+        "log-scale",
+        &[Key::Char('L')],
+        "toggle logarithmic scale for graph and sparkline",
+    ),
+    (
+        Action::Sparkline,
+        "sparkline",
+        &[Key::Char('s')],
+        "toggle the population sparkline",
+    ),
+    (
+        Action::Census,
+        "census",
+        &[Key::Char('o')],
+        "toggle a census of the objects in the world",
+    ),
+    (
+        Action::Command,
+        "command",
+        &[Key::Char(':')],
+        "enter a command, one of\n  rules <B../S..>, fps <n>, fading <n>,\n  alive <#rrggbb>, dead <#rrggbb>, rainbow [on|off],\n  age [on|off], heatmap [on|off|reset],\n  goto <generation>",
+    ),
+    (
+        Action::Snapshot,
+        "snapshot",
+        &[Key::Char('P')],
+        "save the current frame as PNG in the working directory",
+    ),
+    (
+        Action::Svg,
+        "svg",
+        &[Key::Char('V')],
+        "save the selection or the whole world as SVG in the\nworking directory in pause/drawing mode",
+    ),
+    (
+        Action::StatusBar,
+        "status-bar",
+        &[Key::Char('S')],
+        "toggle the status bar",
+    ),
+    (
+        Action::Debug,
+        "debug",
+        &[Key::Char('d')],
+        "show debug info",
+    ),
+    (
+        Action::Keys,
+        "keys",
+        &[Key::Char('?')],
+        "show the current key bindings",
+    ),
+    (
+        Action::Quit,
+        "quit",
+        &[Key::Char('q')],
+        "quit, ctrl-c always quits",
+    ),
+];
+
+const KEY_NAMES: [(Key, &str); 14] = [
+    (Key::Backspace, "backspace"),
+    (Key::Char(','), "comma"),
+    (Key::Delete, "delete"),
+    (Key::Down, "down"),
+    (Key::End, "end"),
+    (Key::Char('\n'), "enter"),
+    (Key::Esc, "esc"),
+    (Key::Home, "home"),
+    (Key::Left, "left"),
+    (Key::PageDown, "pagedown"),
+    (Key::PageUp, "pageup"),
+    (Key::Right, "right"),
+    (Key::Char(' '), "space"),
+    (Key::Char('\t'), "tab"),
+];
+
+/// The keys of every action, in the order of the keyboard controls.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyBindings {
+    bindings: Vec<(Action, Vec<Key>)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            bindings: ACTIONS
+                .iter()
+                .map(|(action, _, keys, _)| (*action, keys.to_vec()))
+                .collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Parses comma separated `action=key` pairs which replace the default
+    /// keys of the actions, e.g. `pause=P,cursor-left=a,cursor-left=left`.
+    /// A rebound key is removed from the action it was bound to before.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let error = |message: String| {
+            Command::new("set argument to e.g. pause=P,cursor-left=a,cursor-left=left")
+                .error(ErrorKind::InvalidValue, message)
+        };
+
+        let mut key_bindings = Self::default();
+        let mut rebound: Vec<Action> = Vec::new();
+
+        for binding in s.split(',').map(str::trim).filter(|b| !b.is_empty()) {
+            let (name, key) = binding
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected action=key, got '{binding}'")))?;
+            let action = ACTIONS
+                .iter()
+                .find(|(_, n, _, _)| *n == name.trim())
+                .map(|(action, _, _, _)| *action)
+                .ok_or_else(|| error(format!("unknown action '{}'", name.trim())))?;
+            let key = parse_key(key.trim()).ok_or_else(|| error(format!("unknown key '{key}'")))?;
+
+            for (_, keys) in &mut key_bindings.bindings {
+                keys.retain(|k| *k != key);
+            }
+            let keys = key_bindings.keys_mut(action);
+            if !rebound.contains(&action) {
+                rebound.push(action);
+                keys.clear();
+            }
+            keys.push(key);
+        }
+
+        Ok(key_bindings)
+    }
+
+    fn keys_mut(&mut self, action: Action) -> &mut Vec<Key> {
+        &mut self
+            .bindings
+            .iter_mut()
+            .find(|(a, _)| *a == action)
+            .unwrap()
+            .1
+    }
+
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&key))
+            .map(|(action, _)| *action)
+    }
+
+    /// Lists the keys, names and descriptions of all actions, descriptions
+    /// which span multiple lines are indented.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for ((_, keys), (_, name, _, description)) in self.bindings.iter().zip(ACTIONS.iter()) {
+            let keys = if keys.is_empty() {
+                "-".to_string()
+            } else {
+                keys.iter().map(key_name).collect::<Vec<_>>().join(", ")
+            };
+            let mut description = description.lines();
+            lines.push(format!(
+                "{keys:<11} {name:<14} - {}",
+                description.next().unwrap_or_default()
+            ));
+            lines.extend(description.map(|line| format!("{:<29}{line}", "")));
+        }
+
+        lines
+    }
+
+    /// The keyboard controls as shown in the help.
+    pub fn help(&self) -> String {
+        let mut help =
+            String::from("Keyboard controls, rebind them with --keys <action>=<key>,...:\n");
+        for line in self.lines() {
+            help.push_str("  ");
+            help.push_str(&line);
+            help.push('\n');
+        }
+
+        help
+    }
+}
+
+fn parse_key(s: &str) -> Option<Key> {
+    if let Some((key, _)) = KEY_NAMES.iter().find(|(_, name)| *name == s) {
+        return Some(*key);
+    }
+
+    let single_char = |s: &str| {
+        let mut chars = s.chars();
+        chars.next().filter(|_| chars.next().is_none())
+    };
+
+    if let Some(c) = s.strip_prefix("ctrl-").and_then(single_char) {
+        Some(Key::Ctrl(c))
+    } else if let Some(c) = s.strip_prefix("alt-").and_then(single_char) {
+        Some(Key::Alt(c))
+    } else if let Some(n) = s.strip_prefix('f').and_then(|n| n.parse().ok()) {
+        (1..=12).contains(&n).then_some(Key::F(n))
+    } else {
+        single_char(s).map(Key::Char)
+    }
+}
+
+pub fn key_name(key: &Key) -> String {
+    if let Some((_, name)) = KEY_NAMES.iter().find(|(k, _)| k == key) {
+        return name.to_string();
+    }
+
+    match key {
+        Key::Char(c) => c.to_string(),
+        Key::Ctrl(c) => format!("ctrl-{c}"),
+        Key::Alt(c) => format!("alt-{c}"),
+        Key::F(n) => format!("f{n}"),
+        key => format!("{key:?}").to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_binds_vim_and_arrow_keys_by_default() {
+        let key_bindings = KeyBindings::default();
+        assert_eq!(
+            key_bindings.action(Key::Char('h')),
+            Some(Action::CursorLeft)
+        );
+        assert_eq!(key_bindings.action(Key::Left), Some(Action::CursorLeft));
+        assert_eq!(key_bindings.action(Key::Char('?')), Some(Action::Keys));
+        assert_eq!(key_bindings.action(Key::Char('x')), None);
+    }
+
+    #[test]
+    fn it_rebinds_keys() {
+        let key_bindings = KeyBindings::parse("pause=h, cursor-left=a,cursor-left=ctrl-b").unwrap();
+        assert_eq!(key_bindings.action(Key::Char('h')), Some(Action::Pause));
+        assert_eq!(key_bindings.action(Key::Char('p')), None);
+        assert_eq!(
+            key_bindings.action(Key::Char('a')),
+            Some(Action::CursorLeft)
+        );
+        assert_eq!(
+            key_bindings.action(Key::Ctrl('b')),
+            Some(Action::CursorLeft)
+        );
+        assert_eq!(key_bindings.action(Key::Left), None);
+        assert!(key_bindings.lines()[0].starts_with("h           pause"));
+
+        assert!(KeyBindings::parse("pause").is_err());
+        assert!(KeyBindings::parse("jump=j").is_err());
+        assert!(KeyBindings::parse("pause=shift-p").is_err());
+    }
+}
@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::BuildHasherDefault;
+use std::hash::Hasher;
+
+use term2d::model::point::Point;
+
+pub type PointHashMap<V> = HashMap<Point, V, BuildHasherDefault<PointHasher>>;
+pub type PointHashSet = HashSet<Point, BuildHasherDefault<PointHasher>>;
+
+/// A fast multiplicative hasher in the style of FxHash. The default SipHash
+/// dominates the time spent updating a universe, which hashes every point
+/// many times per generation and does not need its protection against
+/// malicious inputs.
+#[derive(Default)]
+pub struct PointHasher {
+    hash: u64,
+}
+
+impl PointHasher {
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+}
+
+impl Hasher for PointHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.add(byte as u64);
+        }
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.add(i as u32 as u64);
+    }
+}
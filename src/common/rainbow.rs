@@ -1,70 +1,181 @@
+use std::f32::consts::PI;
+use std::fmt::Display;
+
 use term2d::model::point::Point;
 use term2d::model::rgba::Rgba;
 
+use super::args::Args;
+use super::gradient::Gradient;
+
 const CORRECTION_FACTOR_X: f32 = 0.5;
 const CORRECTION_FACTOR_Y: f32 = 1.0;
 
-#[derive(Debug)]
+/// The shape of the rainbow.
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum RainbowMode {
+    /// Stripes perpendicular to the angle, along the screen diagonal by default
+    Linear,
+    /// Rings around the center of the screen
+    Radial,
+    /// Rays around the center of the screen, starting at the angle
+    Conic,
+}
+
+#[derive(Clone, Debug)]
 pub struct Rainbow {
-    colors: Vec<Rgba>,
-    normal: (f32, f32),
-    normalized_color_distance: f32,
-    radius: f32,
-    size: Point,
+    angle: Option<f32>,
+    drift: f32,
+    gradient: Gradient,
+    mode: RainbowMode,
+    shift: f32,
     size_corrected: (f32, f32),
 }
 
+impl From<&Args> for Rainbow {
+    fn from(args: &Args) -> Self {
+        Self::new(
+            args.rainbow_colors.clone(),
+            args.rainbow_mode,
+            args.rainbow_angle,
+            args.rainbow_drift,
+        )
+    }
+}
+
+impl Display for Rainbow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.mode)?;
+        match self.angle {
+            Some(angle) => write!(f, " at {angle}°")?,
+            None => write!(f, " along the diagonal")?,
+        }
+        write!(f, ", drift {}, colors {}", self.drift, self.gradient)
+    }
+}
+
 impl Rainbow {
-    pub fn new(colors: Vec<Rgba>) -> Self {
-        let radius = 1_f32;
-        let normal = (1_f32, 0_f32);
-        let normalized_color_distance = 1_f32 / (colors.len() as f32 - 1_f32);
-        let size = Point::new(1, 0);
-        let size_corrected = (1_f32, 0_f32);
+    /// Creates a rainbow of a gradient between the positions 0 and 1, with
+    /// an angle in degrees and a drift per frame as a fraction of the
+    /// gradient.
+    pub fn new(gradient: Gradient, mode: RainbowMode, angle: Option<f32>, drift: f32) -> Self {
         Self {
-            colors,
-            normal,
-            normalized_color_distance,
-            radius,
-            size,
-            size_corrected,
+            angle,
+            drift,
+            gradient,
+            mode,
+            shift: 0.0,
+            size_corrected: (1.0, 0.0),
         }
     }
 
     pub fn resize(&mut self, size: &Point) {
-        self.size = size.clone();
         self.size_corrected = (
             CORRECTION_FACTOR_X * size.x as f32,
             CORRECTION_FACTOR_Y * size.y as f32,
         );
-        self.radius = ((self.size_corrected.0 * self.size_corrected.0
-            + self.size_corrected.1 * self.size_corrected.1) as f32)
-            .sqrt();
-        self.normal = (
-            self.size_corrected.0 as f32 / self.radius,
-            self.size_corrected.1 as f32 / self.radius,
-        );
+    }
+
+    /// Moves an animated rainbow one frame further, returns whether it
+    /// changed.
+    pub fn drift(&mut self) -> bool {
+        if self.drift == 0.0 {
+            return false;
+        }
+
+        self.shift = (self.shift + self.drift).rem_euclid(1.0);
+        true
     }
 
     pub fn at(&self, point: Point) -> Rgba {
-        let point_corrected = (
+        let position = self.position(&point);
+        let position = if self.drift == 0.0 {
+            position
+        } else {
+            (position + self.shift).rem_euclid(1.0)
+        };
+
+        self.gradient.at(position)
+    }
+
+    /// Maps a point to the range 0..=1 of the gradient.
+    fn position(&self, point: &Point) -> f32 {
+        let (width, height) = self.size_corrected;
+        let (x, y) = (
             CORRECTION_FACTOR_X * point.x as f32,
             CORRECTION_FACTOR_Y * point.y as f32,
         );
-        let point_projection = (point_corrected.0 * self.size_corrected.0
-            + point_corrected.1 * self.size_corrected.1)
-            / (self.radius * self.radius);
+        let (dx, dy) = (x - width / 2.0, y - height / 2.0);
+
+        let position = match (self.mode, self.angle) {
+            (RainbowMode::Linear, None) => {
+                (x * width + y * height) / (width * width + height * height)
+            }
+            (RainbowMode::Linear, Some(angle)) => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let extent = (width * cos).abs() + (height * sin).abs();
+                0.5 + (dx * cos + dy * sin) / extent
+            }
+            (RainbowMode::Radial, _) => {
+                (dx * dx + dy * dy).sqrt() / (width * width + height * height).sqrt() * 2.0
+            }
+            (RainbowMode::Conic, angle) => {
+                let start = angle.unwrap_or(0.0).to_radians();
+                (dy.atan2(dx) - start).rem_euclid(2.0 * PI) / (2.0 * PI)
+            }
+        };
 
-        let i = ((self.colors.len() - 1) as f32 * point_projection) as usize;
-        let color1 = self.colors[i].clone();
-        let color2 = self.colors[i + 1].clone();
+        if position.is_finite() {
+            position
+        } else {
+            0.0
+        }
+    }
+}
 
-        let a = point_projection / self.normalized_color_distance - i as f32;
+#[cfg(test)]
+mod test {
+    use super::*;
 
-        let r = ((1_f32 - a) * color1.r as f32 + a * color2.r as f32) as u8;
-        let g = ((1_f32 - a) * color1.g as f32 + a * color2.g as f32) as u8;
-        let b = ((1_f32 - a) * color1.b as f32 + a * color2.b as f32) as u8;
+    fn rainbow(mode: RainbowMode, angle: Option<f32>) -> Rainbow {
+        let gradient = Gradient::parse_colors("#000000,#ffffff").unwrap();
+        let mut rainbow = Rainbow::new(gradient, mode, angle, 0.0);
+        rainbow.resize(&Point::new(20, 10));
+        rainbow
+    }
+
+    #[test]
+    fn it_colors_the_far_corner_with_the_last_color() {
+        let rainbow = rainbow(RainbowMode::Linear, None);
+        assert_eq!(rainbow.at(Point::new(0, 0)), Rgba::black());
+        assert_eq!(rainbow.at(Point::new(20, 10)), Rgba::white());
+        assert_eq!(rainbow.at(Point::new(40, 20)), Rgba::white());
+    }
+
+    #[test]
+    fn it_projects_along_the_angle() {
+        let rainbow = rainbow(RainbowMode::Linear, Some(90.0));
+        assert_eq!(rainbow.position(&Point::new(0, 0)), 0.0);
+        assert_eq!(rainbow.position(&Point::new(20, 0)), 0.0);
+        assert_eq!(rainbow.position(&Point::new(20, 10)), 1.0);
+    }
+
+    #[test]
+    fn it_draws_radial_and_conic_gradients() {
+        let radial = rainbow(RainbowMode::Radial, None);
+        assert_eq!(radial.position(&Point::new(10, 5)), 0.0);
+        assert_eq!(radial.position(&Point::new(0, 0)), 1.0);
+
+        let conic = rainbow(RainbowMode::Conic, None);
+        assert_eq!(conic.position(&Point::new(15, 5)), 0.0);
+        assert_eq!(conic.position(&Point::new(5, 5)), 0.5);
+    }
 
-        Rgba { r, g, b, a: 255 }
+    #[test]
+    fn it_drifts_over_time() {
+        let gradient = Gradient::parse_colors("#000000,#ffffff").unwrap();
+        let mut rainbow = Rainbow::new(gradient, RainbowMode::Linear, Some(0.0), 0.25);
+        rainbow.resize(&Point::new(20, 10));
+        assert!(rainbow.drift());
+        assert_eq!(rainbow.at(Point::new(10, 0)), rainbow.gradient.at(0.75));
     }
 }
@@ -0,0 +1,27 @@
+use clap::Command;
+use clap::Error;
+use clap::ErrorKind;
+use term2d::model::point::Point;
+
+/// Parses a size like `100x50`.
+pub fn parse_size(s: &str) -> Result<Point, Error> {
+    s.split_once('x')
+        .and_then(|(width, height)| Some(Point::new(width.parse().ok()?, height.parse().ok()?)))
+        .filter(|size| size.width() > 0 && size.height() > 0)
+        .ok_or_else(|| {
+            Command::new("set argument to e.g. 100x50")
+                .error(ErrorKind::InvalidValue, "invalid size")
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_sizes() {
+        assert_eq!(parse_size("100x50").unwrap(), Point::new(100, 50));
+        assert!(parse_size("100").is_err());
+        assert!(parse_size("0x50").is_err());
+    }
+}
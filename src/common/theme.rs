@@ -0,0 +1,219 @@
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use clap::Command;
+use clap::Error;
+use clap::ErrorKind;
+use term2d::model::color::Color;
+use term2d::model::rgba::Rgba;
+
+use super::color::RgbaParser;
+use super::config::parse_entries;
+use super::gradient::Gradient;
+
+const THEME_DIRECTORY: &str = "themes";
+const THEME_EXTENSION: &str = "toml";
+
+/// The built-in themes as name, alive, dead, cursor, text, text background
+/// and rainbow colors.
+const THEME_PRESETS: [[&str; 7]; 5] = [
+    [
+        "default",
+        "#EE8822",
+        "#113011",
+        "#FFFFFF",
+        "#C8C8C8",
+        "#000000",
+        "#FF0000,#FFFF00,#00FF00,#00FFFF,#0000FF,#FF00FF",
+    ],
+    [
+        "matrix",
+        "#00FF41",
+        "#0D0208",
+        "#CCFFCC",
+        "#00C832",
+        "#000000",
+        "#003B00,#008F11,#00FF41,#CCFFCC",
+    ],
+    [
+        "solarized",
+        "#B58900",
+        "#002B36",
+        "#FDF6E3",
+        "#93A1A1",
+        "#073642",
+        "#B58900,#CB4B16,#DC322F,#D33682,#6C71C4,#268BD2,#2AA198,#859900",
+    ],
+    [
+        "amber",
+        "#FFB000",
+        "#1A0F00",
+        "#FFE0A0",
+        "#FFB000",
+        "#000000",
+        "#4D2E00,#A66300,#FFB000,#FFE0A0",
+    ],
+    [
+        "paper",
+        "#2B2B2B",
+        "#F4F1E8",
+        "#C03030",
+        "#2B2B2B",
+        "#E0DCCF",
+        "#2B2B2B,#5A5A8A,#8A5A5A",
+    ],
+];
+
+/// The directory of the user configuration, following the XDG base directory
+/// specification.
+pub fn config_directory() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|directory| !directory.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(base.join("termgol"))
+}
+
+/// A named set of colors for the world, the cursor, text and the rainbow.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub alive: Rgba,
+    pub cursor: Rgba,
+    pub dead: Rgba,
+    pub name: String,
+    pub rainbow: Gradient,
+    pub text: Rgba,
+    pub text_background: Rgba,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset("default").unwrap()
+    }
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl Theme {
+    /// Parses the name of a built-in theme, of a theme file in the themes
+    /// directory of the user configuration or the path of a theme file.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        if let Some(theme) = Self::preset(s) {
+            return Ok(theme);
+        }
+
+        let user_theme = config_directory()
+            .map(|directory| {
+                directory
+                    .join(THEME_DIRECTORY)
+                    .join(format!("{s}.{THEME_EXTENSION}"))
+            })
+            .filter(|path| path.exists());
+        let path = user_theme.unwrap_or_else(|| PathBuf::from(s));
+
+        let content = fs::read_to_string(&path).map_err(|_| {
+            let presets: Vec<&str> = THEME_PRESETS.iter().map(|preset| preset[0]).collect();
+            Command::new(format!(
+                "set argument to one of {} or the name or path of a theme file",
+                presets.join(", ")
+            ))
+            .error(ErrorKind::InvalidValue, "unknown theme")
+        })?;
+
+        Self::from_file(s, &path, &content)
+    }
+
+    fn preset(name: &str) -> Option<Self> {
+        let [name, alive, dead, cursor, text, text_background, rainbow] =
+            THEME_PRESETS.iter().find(|preset| preset[0] == name)?;
+
+        Some(Self {
+            alive: RgbaParser::parse(alive).unwrap(),
+            cursor: RgbaParser::parse(cursor).unwrap(),
+            dead: RgbaParser::parse(dead).unwrap(),
+            name: name.to_string(),
+            rainbow: Gradient::parse_colors(rainbow).unwrap(),
+            text: RgbaParser::parse(text).unwrap(),
+            text_background: RgbaParser::parse(text_background).unwrap(),
+        })
+    }
+
+    /// Reads `key = "value"` lines like the config file, colors which are
+    /// missing are taken from the default theme.
+    fn from_file(name: &str, path: &Path, content: &str) -> Result<Self, Error> {
+        let error = |line: usize, message: &str| {
+            Command::new(format!("fix line {line} of {}", path.display()))
+                .error(ErrorKind::InvalidValue, message)
+        };
+
+        let mut theme = Self {
+            name: name.to_string(),
+            ..Self::default()
+        };
+
+        for entry in parse_entries(content) {
+            let (line, key, value) = entry.map_err(|(line, message)| error(line, &message))?;
+            let color = || RgbaParser::parse(&value).map_err(|_| error(line, "invalid color"));
+
+            match key.as_str() {
+                "alive" => theme.alive = color()?,
+                "cursor" => theme.cursor = color()?,
+                "dead" => theme.dead = color()?,
+                "rainbow" => {
+                    theme.rainbow = Gradient::parse_colors(&value)
+                        .map_err(|_| error(line, "invalid rainbow colors"))?
+                }
+                "text" => theme.text = color()?,
+                "text_background" => theme.text_background = color()?,
+                key => return Err(error(line, &format!("unknown key '{key}'"))),
+            }
+        }
+
+        Ok(theme)
+    }
+
+    pub fn text_color(&self) -> Color {
+        Color {
+            bg: self.text_background.clone(),
+            fg: self.text.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_built_in_themes() {
+        for preset in THEME_PRESETS {
+            assert_eq!(Theme::parse(preset[0]).unwrap().name, preset[0]);
+        }
+        assert!(Theme::parse("no-such-theme").is_err());
+    }
+
+    #[test]
+    fn it_reads_theme_files() {
+        let path = Path::new("neon.toml");
+        let theme = Theme::from_file(
+            "neon",
+            path,
+            "# neon lights\nalive = \"#ff00ff\" # pink\nrainbow = '#ff00ff,#00ffff'\n",
+        )
+        .unwrap();
+
+        assert_eq!(theme.alive, Rgba::violet());
+        assert_eq!(theme.dead, Theme::default().dead);
+        assert_eq!(theme.rainbow.at(1.0), Rgba::cyan());
+        assert!(Theme::from_file("neon", path, "alive = \"pink\"").is_err());
+        assert!(Theme::from_file("neon", path, "glow = \"#ffffff\"").is_err());
+    }
+}
@@ -1,7 +1,10 @@
+use std::time::Instant;
+
 use term2d::model::event::Event;
 use term2d::model::key::Key;
-use term2d::view::canvas::halfblock::HalfblockCanvas;
 
+use crate::common::key_bindings::Action;
+use crate::renderer::canvas::TerminalCanvas;
 use crate::renderer::Renderer;
 use crate::state::State;
 
@@ -12,44 +15,97 @@ pub struct Controller {
 
 impl From<State> for Controller {
     fn from(state: State) -> Self {
-        let renderer = Renderer::new();
+        let renderer = Renderer::new(&state.args);
 
         Self { renderer, state }
     }
 }
 
-impl term2d::controller::Controller<HalfblockCanvas> for Controller {
+impl term2d::controller::Controller<TerminalCanvas> for Controller {
     fn update(&mut self, event: Event) -> bool {
         match event {
-            Event::Key(key) => match key {
-                Key::Char('q') => return false,
-                Key::Ctrl('c') => return false,
+            Event::Key(Key::Ctrl('c')) => return false,
+            Event::Key(key) if self.state.command_line.is_some() => match key {
+                Key::Char('\n') => self.state.submit_command_line(),
+                Key::Char(c) => self.state.push_command_line(c),
+                Key::Backspace => self.state.pop_command_line(),
+                Key::Esc => self.state.close_command_line(),
+                _ => {}
+            },
+            Event::Key(key) => {
+                self.state.clear_message();
+                self.state.record_key(&key);
 
-                Key::Char('h') => self.state.move_cursor_left(),
-                Key::Char('l') => self.state.move_cursor_right(),
-                Key::Char('k') => self.state.move_cursor_up(),
-                Key::Char('j') => self.state.move_cursor_down(),
+                match self.state.args.keys.action(key) {
+                    Some(Action::Quit) => return false,
 
-                Key::Char('p') => self.state.toggle_pause(),
-                Key::Char('d') => self.state.debug_info_next_page(),
+                    Some(Action::CursorLeft) => self.state.move_cursor_left(),
+                    Some(Action::CursorRight) => self.state.move_cursor_right(),
+                    Some(Action::CursorUp) => self.state.move_cursor_up(),
+                    Some(Action::CursorDown) => self.state.move_cursor_down(),
 
-                Key::Char(' ') => self.state.toggle_life_at_cursor(),
+                    Some(Action::Pause) => self.state.toggle_pause(),
+                    Some(Action::Step) => self.state.step(),
+                    Some(Action::FastForward) => self.state.toggle_fast_forward(),
+                    Some(Action::Debug) => self
+                        .state
+                        .debug_info_next_page(Renderer::debug_info_page_count()),
+                    Some(Action::Keys) => self.state.toggle_key_help(),
+                    Some(Action::Snapshot) => self.state.snapshot(),
+                    Some(Action::Svg) => self.state.snapshot_svg(),
+                    Some(Action::StatusBar) => {
+                        self.state.toggle_status_bar();
+                        self.state
+                            .resize_world(&self.renderer.world_size(&self.state));
+                    }
 
-                _ => {}
-            },
+                    Some(Action::ToggleCell) => self.state.toggle_life_at_cursor(),
+                    Some(Action::Select) => self.state.toggle_selection(),
+                    Some(Action::Identify) => self.state.identify(),
+
+                    Some(Action::NextRules) => self.state.next_rules(),
+                    Some(Action::PreviousRules) => self.state.previous_rules(),
+                    Some(Action::Faster) => self.state.increase_frames_per_second(),
+                    Some(Action::Slower) => self.state.decrease_frames_per_second(),
+                    Some(Action::FadeFaster) => self.state.increase_fading_speed(),
+                    Some(Action::FadeSlower) => self.state.decrease_fading_speed(),
+                    Some(Action::NextColor) => self.state.next_color_bg_alive(),
+                    Some(Action::Rainbow) => self.state.toggle_rainbow(),
+                    Some(Action::Age) => self.state.toggle_color_by_age(),
+                    Some(Action::Heatmap) => self.state.toggle_heatmap(),
+
+                    Some(Action::Graph) => self.state.toggle_graph(),
+                    Some(Action::BirthsDeaths) => self.state.toggle_graph_births_and_deaths(),
+                    Some(Action::LogScale) => self.state.toggle_graph_logarithmic(),
+                    Some(Action::GraphTaller) => self.state.increase_graph_height(),
+                    Some(Action::GraphShorter) => self.state.decrease_graph_height(),
+                    Some(Action::Sparkline) => self.state.toggle_sparkline(),
+                    Some(Action::Census) => self.state.toggle_census(),
+
+                    Some(Action::Command) => self.state.open_command_line(),
+
+                    None => {}
+                }
+            }
             Event::Resize => {
-                let size = self.renderer.resize();
-                self.state.resize(size);
+                self.renderer.resize();
+                self.state.resize(&self.renderer.world_size(&self.state));
+            }
+            Event::Elapse => {
+                if !self.state.elapse_time() {
+                    return true;
+                }
             }
-            Event::Elapse => self.state.elapse_time(),
         }
 
+        let start = Instant::now();
         self.renderer.display(&self.state);
+        self.state.timing.record_render(start.elapsed());
 
         true
     }
 
-    fn get_canvas(&mut self) -> &mut HalfblockCanvas {
+    fn get_canvas(&mut self) -> &mut TerminalCanvas {
         &mut self.renderer.canvas
     }
 }
@@ -0,0 +1,234 @@
+use std::cmp::max;
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::iter;
+use std::path::Path;
+
+use crate::common::args::Args;
+use crate::common::args::ExportArgs;
+use crate::state::world::World;
+use term2d::model::point::Point;
+use term2d::model::rect::Rect;
+
+use self::gif::GifEncoder;
+
+pub mod gif;
+pub mod png;
+pub mod svg;
+
+/// The number of generations of an animation if none is given.
+const DEFAULT_GIF_GENERATIONS: u64 = 100;
+
+/// The size of a cell in pixels in snapshots.
+const SNAPSHOT_CELL_SIZE: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// An animation of every generation
+    Gif,
+    /// The last generation
+    Png,
+    /// The last generation as vector image, see --svg-caption and --svg-grid
+    Svg,
+}
+
+/// The pixels of a world in rows from top to bottom.
+pub struct Image {
+    pub height: usize,
+    pub pixels: Vec<[u8; 3]>,
+    pub width: usize,
+}
+
+impl Image {
+    /// Draws every cell of the world as a square of pixels in the color it
+    /// is shown in, including fading and rainbow colors.
+    pub fn from_world(world: &World, cell_size: usize) -> Self {
+        let columns = world.size.width() as usize;
+        let rows = world.size.height() as usize;
+        let width = columns * cell_size;
+        let mut pixels = Vec::with_capacity(width * rows * cell_size);
+
+        for y in 0..rows {
+            let row: Vec<[u8; 3]> = (0..columns)
+                .flat_map(|x| {
+                    let color = world.visible_color(y * columns + x);
+                    iter::repeat_n([color.r, color.g, color.b], cell_size)
+                })
+                .collect();
+            for _ in 0..cell_size {
+                pixels.extend(&row);
+            }
+        }
+
+        Self {
+            height: rows * cell_size,
+            pixels,
+            width,
+        }
+    }
+}
+
+/// Computes the generations of a cell setup in a toroidal world of a fixed
+/// size and writes them as an animation or the last of them as an image.
+pub fn run(args: Args, export_args: ExportArgs) -> io::Result<()> {
+    let frames_per_second = export_args
+        .frames_per_second
+        .unwrap_or(args.frames_per_second);
+    let mut world = World::from(Args {
+        cell_setup: export_args
+            .cell_setup
+            .clone()
+            .unwrap_or_else(|| args.cell_setup.clone()),
+        screen_saver: None,
+        ..args.clone()
+    });
+    world.resize(&export_args.size);
+    world.setup_cells();
+    let cell_size = export_args.cell_size.into();
+    let generations = export_args.generations.unwrap_or(match export_args.format {
+        ExportFormat::Gif => DEFAULT_GIF_GENERATIONS,
+        ExportFormat::Png | ExportFormat::Svg => 0,
+    });
+
+    match export_args.format {
+        ExportFormat::Gif => {
+            check_gif_size(&export_args.size, cell_size)?;
+            let first_frame = Image::from_world(&world, cell_size);
+            // browsers show frames with shorter delays than 2/100 s slower
+            let delay = max(2, (100.0 / max(1, frames_per_second) as f64).round() as u16);
+            let out = BufWriter::new(File::create(&export_args.output)?);
+            let mut encoder = GifEncoder::new(out, first_frame.width, first_frame.height, delay)?;
+            encoder.write_frame(&first_frame)?;
+
+            for _ in 0..generations {
+                world.drift_rainbow();
+                world.update();
+                encoder.write_frame(&Image::from_world(&world, cell_size))?;
+            }
+            encoder.finish()
+        }
+        ExportFormat::Png | ExportFormat::Svg => {
+            for _ in 0..generations {
+                world.drift_rainbow();
+                world.update();
+            }
+            if export_args.format == ExportFormat::Png {
+                write_png(&export_args.output, &Image::from_world(&world, cell_size))
+            } else {
+                let area = Rect::new(0, 0, world.size.width(), world.size.height());
+                write_svg(&export_args.output, &world, &area, cell_size, &args)
+            }
+        }
+    }
+}
+
+/// Fails if an animation of a world of the given size does not fit the 16 bit
+/// width and height of GIF.
+fn check_gif_size(size: &Point, cell_size: usize) -> io::Result<()> {
+    let max_cells = u16::MAX as usize / cell_size;
+    if size.width() as usize > max_cells || size.height() as usize > max_cells {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "GIF animations are at most {} pixels wide and high, use at most {max_cells}x{max_cells} cells with a cell size of {cell_size}",
+                u16::MAX
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Saves the current frame of the world as PNG in the working directory and
+/// returns its path.
+pub fn snapshot(world: &World) -> io::Result<String> {
+    let path = format!("termgol-generation-{}.png", world.generation);
+    write_png(
+        Path::new(&path),
+        &Image::from_world(world, SNAPSHOT_CELL_SIZE),
+    )?;
+
+    Ok(path)
+}
+
+/// Saves an area of the world as SVG in the working directory and returns
+/// its path.
+pub fn snapshot_svg(world: &World, area: &Rect, args: &Args) -> io::Result<String> {
+    let path = format!("termgol-generation-{}.svg", world.generation);
+    write_svg(Path::new(&path), world, area, SNAPSHOT_CELL_SIZE, args)?;
+
+    Ok(path)
+}
+
+fn write_png(path: &Path, image: &Image) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    png::write(&mut out, image)?;
+    out.flush()
+}
+
+fn write_svg(
+    path: &Path,
+    world: &World,
+    area: &Rect,
+    cell_size: usize,
+    args: &Args,
+) -> io::Result<()> {
+    let caption = args.svg_caption.then(|| match world.rules.name() {
+        Some(name) => format!("{} {name}, generation {}", world.rules, world.generation),
+        None => format!("{}, generation {}", world.rules, world.generation),
+    });
+
+    let mut out = BufWriter::new(File::create(path)?);
+    svg::write(
+        &mut out,
+        world,
+        area,
+        cell_size,
+        args.svg_grid,
+        caption.as_deref(),
+    )?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::cell_setup::CellSetup;
+
+    #[test]
+    fn it_draws_cells_as_squares() {
+        let mut world = World::from(Args {
+            cell_setup: CellSetup::parse("acorn").unwrap(),
+            ..Args::default()
+        });
+        world.resize(&Point::new(10, 5));
+        world.setup_cells();
+
+        let image = Image::from_world(&world, 2);
+        let alive = [
+            world.color_bg_alive.r,
+            world.color_bg_alive.g,
+            world.color_bg_alive.b,
+        ];
+        let dead = [
+            world.color_bg_dead.r,
+            world.color_bg_dead.g,
+            world.color_bg_dead.b,
+        ];
+
+        assert_eq!((image.width, image.height), (20, 10));
+        assert_eq!(image.pixels.len(), 200);
+        assert_eq!(image.pixels.iter().filter(|p| **p == alive).count(), 7 * 4);
+        assert_eq!(image.pixels[0], dead);
+    }
+
+    #[test]
+    fn it_refuses_gifs_larger_than_16_bit() {
+        assert!(check_gif_size(&Point::new(16383, 2), 4).is_ok());
+        assert!(check_gif_size(&Point::new(16384, 2), 4).is_err());
+        assert!(check_gif_size(&Point::new(2, 20000), 4).is_err());
+        assert!(check_gif_size(&Point::new(65535, 1), 1).is_ok());
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::io;
+use std::io::Write;
+
+use super::Image;
+
+const MAX_CODE_SIZE: u8 = 12;
+const MAX_COLORS: usize = 256;
+const MAX_SUB_BLOCK_LENGTH: usize = 255;
+
+/// Writes the frames of an endlessly looping GIF animation. Every frame has
+/// its own color table, frames with more than 256 colors are reduced to 8
+/// bit colors.
+pub struct GifEncoder<W: Write> {
+    delay: u16,
+    out: W,
+}
+
+impl<W: Write> GifEncoder<W> {
+    /// Writes the header of an animation of the given size, every frame is
+    /// shown for the delay in hundredths of a second.
+    pub fn new(mut out: W, width: usize, height: usize, delay: u16) -> io::Result<Self> {
+        out.write_all(b"GIF89a")?;
+        out.write_all(&(width as u16).to_le_bytes())?;
+        out.write_all(&(height as u16).to_le_bytes())?;
+        // no global color table, background color and pixel aspect ratio
+        out.write_all(&[0, 0, 0])?;
+
+        // the application extension which loops the animation forever
+        out.write_all(&[0x21, 0xff, 11])?;
+        out.write_all(b"NETSCAPE2.0")?;
+        out.write_all(&[3, 1, 0, 0, 0])?;
+
+        Ok(Self { delay, out })
+    }
+
+    pub fn write_frame(&mut self, image: &Image) -> io::Result<()> {
+        let (palette, indices) = palettize(&image.pixels);
+        let table_bits = (1..=8).find(|bits| palette.len() <= 1 << bits).unwrap_or(8);
+
+        // the graphic control extension which sets the delay
+        self.out.write_all(&[0x21, 0xf9, 4, 0])?;
+        self.out.write_all(&self.delay.to_le_bytes())?;
+        self.out.write_all(&[0, 0])?;
+
+        self.out.write_all(&[0x2c, 0, 0, 0, 0])?;
+        self.out.write_all(&(image.width as u16).to_le_bytes())?;
+        self.out.write_all(&(image.height as u16).to_le_bytes())?;
+        self.out.write_all(&[0x80 | (table_bits - 1)])?;
+        for i in 0..1 << table_bits {
+            self.out
+                .write_all(&palette.get(i).copied().unwrap_or_default())?;
+        }
+
+        let min_code_size = table_bits.max(2);
+        self.out.write_all(&[min_code_size])?;
+        for sub_block in lzw_encode(&indices, min_code_size).chunks(MAX_SUB_BLOCK_LENGTH) {
+            self.out.write_all(&[sub_block.len() as u8])?;
+            self.out.write_all(sub_block)?;
+        }
+        self.out.write_all(&[0])
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.write_all(&[0x3b])?;
+        self.out.flush()
+    }
+}
+
+/// Converts pixels to a color table and the index of every pixel in it.
+fn palettize(pixels: &[[u8; 3]]) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let mut palette = Vec::new();
+    let mut palette_indices: HashMap<[u8; 3], u8> = HashMap::new();
+    let mut indices = Vec::with_capacity(pixels.len());
+
+    for pixel in pixels {
+        let index = match palette_indices.get(pixel) {
+            Some(&index) => index,
+            None if palette.len() < MAX_COLORS => {
+                let index = palette.len() as u8;
+                palette.push(*pixel);
+                palette_indices.insert(*pixel, index);
+                index
+            }
+            None => return palettize_rgb332(pixels),
+        };
+        indices.push(index);
+    }
+
+    (palette, indices)
+}
+
+/// Reduces pixels to 3 bits of red and green and 2 bits of blue.
+fn palettize_rgb332(pixels: &[[u8; 3]]) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let scale = |value: u32, max: u32| (value * 255 / max) as u8;
+    let palette = (0..=255_u32)
+        .map(|i| [scale(i >> 5, 7), scale(i >> 2 & 7, 7), scale(i & 3, 3)])
+        .collect();
+    let indices = pixels
+        .iter()
+        .map(|[r, g, b]| (r & 0xe0) | (g >> 3 & 0x1c) | (b >> 6))
+        .collect();
+
+    (palette, indices)
+}
+
+/// Packs variable length codes into bytes, least significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    bit_count: u32,
+    bits: u32,
+    bytes: Vec<u8>,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, code_size: u8) {
+        self.bits |= (code as u32) << self.bit_count;
+        self.bit_count += code_size as u32;
+        while self.bit_count >= 8 {
+            self.bytes.push(self.bits as u8);
+            self.bits >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.bytes.push(self.bits as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Compresses color indices with the variable length LZW of GIF, the code
+/// table is cleared once it is full.
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear_code = 1_u16 << min_code_size;
+    let end_code = clear_code + 1;
+
+    let mut writer = BitWriter::default();
+    let mut codes: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut code_size = min_code_size + 1;
+    let mut next_code = end_code + 1;
+    writer.write(clear_code, code_size);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        writer.write(end_code, code_size);
+        return writer.finish();
+    };
+
+    let mut prefix = first as u16;
+    for &index in rest {
+        if let Some(&code) = codes.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+
+        writer.write(prefix, code_size);
+        if next_code < 1 << MAX_CODE_SIZE {
+            codes.insert((prefix, index), next_code);
+            // the decoder learns a code one step later, so the code size
+            // grows once the new code no longer fits
+            if next_code == 1 << code_size {
+                code_size += 1;
+            }
+            next_code += 1;
+        } else {
+            writer.write(clear_code, code_size);
+            codes.clear();
+            code_size = min_code_size + 1;
+            next_code = end_code + 1;
+        }
+        prefix = index as u16;
+    }
+
+    writer.write(prefix, code_size);
+    writer.write(end_code, code_size);
+    writer.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Decodes the LZW of GIF as specified, without the optimizations of the
+    /// encoder.
+    fn lzw_decode(bytes: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear_code = 1_usize << min_code_size;
+        let reset = || -> Vec<Vec<u8>> { (0..clear_code + 2).map(|i| vec![i as u8]).collect() };
+
+        let mut table = reset();
+        let mut code_size = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut indices = Vec::new();
+        let mut position = 0;
+
+        loop {
+            let code = (0..code_size as usize).fold(0, |code, bit| {
+                let bit_position = position + bit;
+                code | ((bytes[bit_position / 8] as usize >> (bit_position % 8) & 1) << bit)
+            });
+            position += code_size as usize;
+
+            if code == clear_code {
+                table = reset();
+                code_size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == clear_code + 1 {
+                return indices;
+            }
+
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [previous.clone(), vec![previous[0]]].concat(),
+                (None, None) => panic!("unknown code {code}"),
+            };
+            indices.extend(&entry);
+            if let Some(previous) = previous {
+                if table.len() < 1 << MAX_CODE_SIZE {
+                    table.push([previous, vec![entry[0]]].concat());
+                }
+            }
+            if table.len() == 1 << code_size && code_size < MAX_CODE_SIZE {
+                code_size += 1;
+            }
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn it_compresses_with_lzw() {
+        let indices: Vec<u8> = (0..20000_u32)
+            .map(|i| (i * i / 7 % 5 + i / 1000 % 3) as u8)
+            .collect();
+        assert_eq!(lzw_decode(&lzw_encode(&indices, 3), 3), indices);
+
+        let indices: Vec<u8> = (0..50000_u32).map(|i| (i * 7919 % 256) as u8).collect();
+        assert_eq!(lzw_decode(&lzw_encode(&indices, 8), 8), indices);
+
+        assert_eq!(lzw_decode(&lzw_encode(&[], 2), 2), vec![]);
+    }
+
+    #[test]
+    fn it_writes_gif() {
+        let image = Image {
+            height: 2,
+            pixels: vec![[0, 0, 0], [255, 255, 255], [255, 255, 255], [0, 0, 0]],
+            width: 2,
+        };
+        let mut encoder = GifEncoder::new(Vec::new(), 2, 2, 10).unwrap();
+        encoder.write_frame(&image).unwrap();
+        let gif = encoder.out.clone();
+        encoder.finish().unwrap();
+
+        assert!(gif.starts_with(b"GIF89a\x02\x00\x02\x00\x00\x00\x00\x21\xff\x0bNETSCAPE2.0"));
+        // the delay and a color table of two colors
+        assert_eq!(gif[32..38], [0x21, 0xf9, 4, 0, 10, 0]);
+        assert_eq!(gif[49..56], [0x80, 0, 0, 0, 255, 255, 255]);
+    }
+
+    #[test]
+    fn it_reduces_colors() {
+        let pixels: Vec<[u8; 3]> = (0..300_u32)
+            .map(|i| [i as u8, (i / 256) as u8, 0])
+            .collect();
+        let (palette, indices) = palettize(&pixels);
+        assert_eq!(palette.len(), 256);
+        assert_eq!(indices[0], 0);
+        assert_eq!(palette[indices[299] as usize], [36, 0, 0]);
+        assert_eq!(palette[0xff], [255, 255, 255]);
+    }
+}
@@ -0,0 +1,140 @@
+use std::io;
+use std::io::Write;
+
+use super::Image;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// The maximum length of a stored deflate block.
+const STORED_BLOCK_LENGTH: usize = 65535;
+
+/// Writes an image as 8 bit RGB PNG. The image data is not compressed, it is
+/// wrapped in stored deflate blocks, which keeps the encoder small.
+pub fn write(out: &mut impl Write, image: &Image) -> io::Result<()> {
+    out.write_all(&SIGNATURE)?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend((image.width as u32).to_be_bytes());
+    header.extend((image.height as u32).to_be_bytes());
+    // bit depth 8, color type RGB, deflate, adaptive filtering, no interlace
+    header.extend([8, 2, 0, 0, 0]);
+    write_chunk(out, b"IHDR", &header)?;
+
+    // every scanline starts with its filter type, which is none
+    let mut scanlines = Vec::with_capacity(image.height * (1 + 3 * image.width));
+    for row in image.pixels.chunks(image.width.max(1)) {
+        scanlines.push(0);
+        scanlines.extend(row.iter().flatten());
+    }
+    write_chunk(out, b"IDAT", &zlib_stored(&scanlines))?;
+
+    write_chunk(out, b"IEND", &[])
+}
+
+fn write_chunk(out: &mut impl Write, chunk_type: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(chunk_type)?;
+    out.write_all(data)?;
+
+    let mut crc = Crc32::default();
+    crc.update(chunk_type);
+    crc.update(data);
+    out.write_all(&crc.finish().to_be_bytes())
+}
+
+/// Wraps data in a zlib stream of stored deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let block_count = data.len().div_ceil(STORED_BLOCK_LENGTH).max(1);
+    let mut zlib = Vec::with_capacity(2 + 5 * block_count + data.len() + 4);
+    // deflate with a 32K window, no preset dictionary, fastest compression
+    zlib.extend([0x78, 0x01]);
+
+    let mut blocks = data.chunks(STORED_BLOCK_LENGTH).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        let length = block.len() as u16;
+        zlib.push(is_final as u8);
+        zlib.extend(length.to_le_bytes());
+        zlib.extend((!length).to_le_bytes());
+        zlib.extend(block);
+    }
+
+    zlib.extend(adler32(data).to_be_bytes());
+    zlib
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+struct Crc32 {
+    crc: u32,
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self { crc: 0xffff_ffff }
+    }
+}
+
+impl Crc32 {
+    fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.crc ^= byte as u32;
+            for _ in 0..8 {
+                let mask = (self.crc & 1).wrapping_neg();
+                self.crc = (self.crc >> 1) ^ (0xedb8_8320 & mask);
+            }
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.crc
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_computes_checksums() {
+        let mut crc = Crc32::default();
+        crc.update(b"IEND");
+        assert_eq!(crc.finish(), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn it_writes_png() {
+        let image = Image {
+            height: 1,
+            pixels: vec![[255, 0, 0], [0, 0, 255]],
+            width: 2,
+        };
+        let mut png = Vec::new();
+        write(&mut png, &image).unwrap();
+
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[16..24], [0, 0, 0, 2, 0, 0, 0, 1]);
+        assert_eq!(&png[37..41], b"IDAT");
+        assert_eq!(
+            png[41..54],
+            [0x78, 0x01, 1, 7, 0, 0xf8, 0xff, 0, 255, 0, 0, 0, 0]
+        );
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+    }
+}
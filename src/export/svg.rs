@@ -0,0 +1,158 @@
+use std::io;
+use std::io::Write;
+
+use term2d::model::point::Point;
+use term2d::model::rect::Rect;
+use term2d::model::rgba::Rgba;
+
+use crate::common::color::RgbaParser;
+use crate::state::world::World;
+
+/// The opacity of grid lines drawn in the alive color over the dead color.
+const GRID_ALPHA: u8 = 64;
+
+/// The width of a character of a monospace font relative to its size.
+const CHAR_WIDTH: f64 = 0.6;
+
+/// Writes the living cells in an area of the world as squares in their
+/// current color. The coordinates are cells, the size of the image is the
+/// cell size in pixels.
+pub fn write(
+    out: &mut impl Write,
+    world: &World,
+    area: &Rect,
+    cell_size: usize,
+    grid: bool,
+    caption: Option<&str>,
+) -> io::Result<()> {
+    let width = area.size.width();
+    let height = area.size.height();
+    let font_size = caption.map_or(0.0, |caption| caption_font_size(width, caption));
+    let caption_height = 2.0 * font_size;
+    let total_height = format!("{:.2}", height as f64 + caption_height);
+
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         viewBox=\"0 0 {width} {total_height}\" shape-rendering=\"crispEdges\">",
+        width as usize * cell_size,
+        ((height as f64 + caption_height) * cell_size as f64).round(),
+    )?;
+    writeln!(
+        out,
+        "  <rect width=\"{width}\" height=\"{total_height}\" fill=\"{}\"/>",
+        RgbaParser::format(&world.color_bg_dead)
+    )?;
+
+    for y in 0..height {
+        for x in 0..width {
+            let p = Point::new(area.pos.x + x, area.pos.y + y);
+            if p.x < 0 || p.y < 0 || p.x >= world.size.width() || p.y >= world.size.height() {
+                continue;
+            }
+            let i = (world.size.width() * p.y + p.x) as usize;
+            if world.cells[i].alive {
+                writeln!(
+                    out,
+                    "  <rect x=\"{x}\" y=\"{y}\" width=\"1\" height=\"1\" fill=\"{}\"/>",
+                    RgbaParser::format(&world.visible_color(i))
+                )?;
+            }
+        }
+    }
+
+    if grid {
+        let vertical = (1..width).map(|x| format!("M{x} 0V{height}"));
+        let horizontal = (1..height).map(|y| format!("M0 {y}H{width}"));
+        let color = Rgba {
+            a: GRID_ALPHA,
+            ..world.color_bg_alive.clone()
+        }
+        .blend(&world.color_bg_dead);
+        writeln!(
+            out,
+            "  <path d=\"{}\" stroke=\"{}\" stroke-width=\"0.05\"/>",
+            vertical.chain(horizontal).collect::<String>(),
+            RgbaParser::format(&color)
+        )?;
+    }
+
+    if let Some(caption) = caption {
+        writeln!(
+            out,
+            "  <text x=\"{:.2}\" y=\"{:.2}\" font-family=\"monospace\" font-size=\"{:.2}\" \
+             fill=\"{}\">{}</text>",
+            font_size * 0.5,
+            height as f64 + font_size * 1.4,
+            font_size,
+            RgbaParser::format(&world.color_bg_alive),
+            escape(caption)
+        )?;
+    }
+
+    writeln!(out, "</svg>")
+}
+
+/// The caption grows with the image so that it stays readable, but shrinks
+/// until it fits the width with a margin of half a character on both sides.
+fn caption_font_size(width: i32, caption: &str) -> f64 {
+    let fitting = width as f64 / (CHAR_WIDTH * caption.chars().count() as f64 + 1.0);
+    fitting.min((width / 40).max(1) as f64)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::args::Args;
+    use crate::state::cell_setup::CellSetup;
+
+    #[test]
+    fn it_writes_living_cells_grid_and_caption() {
+        let mut world = World::from(Args {
+            cell_setup: CellSetup::Special("OO\n".to_string()),
+            ..Args::default()
+        });
+        world.resize(&Point::new(4, 3));
+        world.setup_cells();
+        let alive = RgbaParser::format(&world.color_bg_alive);
+
+        let mut svg = Vec::new();
+        write(
+            &mut svg,
+            &world,
+            &Rect::new(1, 0, 3, 3),
+            10,
+            true,
+            Some("B3678/S34678 day & night"),
+        )
+        .unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        let lines: Vec<&str> = svg.lines().collect();
+
+        assert!(lines[0].starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"30\" height=\"34\" viewBox=\"0 0 3 3.39\""
+        ));
+        assert_eq!(
+            svg.matches("width=\"1\" height=\"1\"").count(),
+            world.statistics.population
+        );
+        assert!(svg.contains(&format!("fill=\"{alive}\"/>")));
+        assert!(svg.contains("<path d=\"M1 0V3M2 0V3M0 1H3M0 2H3\""));
+        assert!(svg.contains(">B3678/S34678 day &amp; night</text>"));
+        assert_eq!(lines.last(), Some(&"</svg>"));
+    }
+
+    #[test]
+    fn it_fits_the_caption_to_the_width() {
+        let caption = "B3/S23 conway's life, generation 100";
+        let font_size = caption_font_size(8, caption);
+        assert!(font_size * (CHAR_WIDTH * caption.chars().count() as f64 + 1.0) <= 8.0);
+        assert_eq!(caption_font_size(400, caption), 10.0);
+    }
+}
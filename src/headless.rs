@@ -0,0 +1,101 @@
+use term2d::model::point::Point;
+
+use crate::common::args::Args;
+use crate::state::cell_image::CellImage;
+use crate::state::census::Census;
+use crate::state::heatmap::SparseHeatmap;
+use crate::state::universe::Universe;
+use crate::state::world::World;
+
+/// Computes the configured number of generations without a terminal ui and
+/// prints the resulting pattern, a census of its objects or the heatmap of the
+/// cell activity to stdout.
+pub fn run(mut args: Args) {
+    let seed = args.resolve_seed();
+    let torus = match (args.width, args.height) {
+        (Some(width), Some(height)) => Some(Point::new(width.into(), height.into())),
+        _ => None,
+    };
+    let (generation, cell_image, heatmap) = match &torus {
+        Some(size) => run_toroidal(&args, size.clone()),
+        None => run_unbounded(&args, seed),
+    };
+
+    if args.census {
+        let census = Census::take(&cell_image, torus.as_ref(), &args.rules, generation);
+        println!(
+            "{} objects in generation {generation}",
+            census.object_count()
+        );
+        for line in census.lines() {
+            println!("{line}");
+        }
+        return;
+    }
+
+    if let Some(heatmap) = heatmap {
+        print!("{heatmap}");
+        return;
+    }
+
+    let mut comments = vec![
+        format!("Generation: {generation}"),
+        format!("Rule: {}", args.rules),
+    ];
+    if args.cell_setup.is_random() {
+        comments.push(format!("Seed: {seed}"));
+    }
+
+    print!(
+        "{}",
+        cell_image.to_pattern(
+            args.format,
+            &args.rules,
+            &comments,
+            [&args.char_alive, &args.char_dead]
+        )
+    );
+}
+
+/// Computes the world and the heatmap as comma separated rows, if it was
+/// requested.
+fn run_toroidal(args: &Args, size: Point) -> (u64, CellImage, Option<String>) {
+    let mut world = World::from(Args {
+        screen_saver: None,
+        ..args.clone()
+    });
+    world.resize(&size);
+    world.setup_cells();
+
+    while world.generation < args.generations {
+        world.update();
+    }
+
+    let heatmap = args.heatmap.then(|| world.heatmap.to_csv());
+    (world.generation, world.cell_image(), heatmap)
+}
+
+fn run_unbounded(args: &Args, seed: u64) -> (u64, CellImage, Option<String>) {
+    let cell_image = CellImage::from_cell_setup(&args.cell_setup, seed);
+    let mut universe = Universe::new(&cell_image, args.rules.clone());
+
+    let mut heatmap = SparseHeatmap::default();
+
+    while universe.generation < args.generations {
+        if !args.heatmap {
+            universe.update();
+            continue;
+        }
+
+        let previous = universe.living_points.clone();
+        universe.update();
+
+        // like in the world, cells which are alive or just died are active
+        for p in previous.union(&universe.living_points) {
+            heatmap.record(p);
+        }
+    }
+
+    let heatmap = args.heatmap.then(|| heatmap.to_csv());
+    (universe.generation, universe.cell_image(), heatmap)
+}
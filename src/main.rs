@@ -1,5 +1,8 @@
-use clap::Parser;
+use std::process;
+
 use common::args::Args;
+use common::args::Subcommand;
+use common::TICKS_PER_SECOND;
 use controller::Controller;
 use state::State;
 use term2d::model::ansiesc::CLEAR_ALL;
@@ -8,21 +11,54 @@ use term2d::model::ansiesc::CURSOR_GOTO_1_1;
 use term2d::model::ansiesc::CURSOR_SHOW;
 use term2d::model::config::Config;
 
+mod bench;
 mod common;
 mod controller;
+mod export;
+mod headless;
 mod renderer;
+mod search;
 mod state;
 
 fn main() {
-    let args = Args::parse();
-    let fps = args.frames_per_second;
-    let state = State::from(args);
+    let mut args = Args::parse_themed();
+
+    match args.command.take() {
+        Some(Subcommand::Bench(bench_args)) => {
+            bench::run(bench_args);
+            return;
+        }
+        Some(Subcommand::Export(export_args)) => {
+            let output = export_args.output.clone();
+            if let Err(error) = export::run(args, export_args) {
+                eprintln!("termgol export: cannot write {}: {error}", output.display());
+                process::exit(1);
+            }
+            return;
+        }
+        Some(Subcommand::Search(search_args)) => {
+            if let Err(error) = search::run(search_args) {
+                eprintln!("termgol search: {error}");
+                process::exit(1);
+            }
+            return;
+        }
+        None => {}
+    }
+
+    if args.headless {
+        headless::run(args);
+        return;
+    }
+
+    let mut state = State::from(args);
+    state.identify_in_background();
     let controller = Controller::from(state);
 
     term2d::run_with_config(
         controller,
         Config {
-            fps,
+            fps: TICKS_PER_SECOND,
             screen_drop_strings: vec![
                 COLOR_RESET.to_string(),
                 CLEAR_ALL.to_string(),
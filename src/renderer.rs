@@ -1,22 +1,49 @@
+use crate::common::args::Args;
+use crate::common::gradient::Gradient;
 use crate::state::State;
+use std::fs::File;
+use std::io::BufWriter;
+use std::process;
+use term2d::model::color::Color;
 use term2d::model::point::Point;
-use term2d::view::canvas::halfblock::HalfblockCanvas;
+use term2d::model::rgba::Rgba;
 use term2d::view::canvas::Canvas;
 
+use self::canvas::TerminalCanvas;
+use self::recorder::Recorder;
+
+pub mod canvas;
+mod census;
+mod command_line;
 mod cursor;
 mod debug_info;
+mod graph;
+mod heatmap;
+mod key_help;
+mod recorder;
+mod selection;
+mod status_bar;
 mod world;
 
 pub struct Renderer {
-    pub canvas: HalfblockCanvas,
-    debug_line_y: i32,
+    pub canvas: TerminalCanvas,
+    heatmap_gradient: Gradient,
+    recorder: Option<Recorder<BufWriter<File>>>,
+    text_color: Color,
 }
 
 impl Renderer {
-    pub fn new() -> Self {
+    pub fn new(args: &Args) -> Self {
         Self {
-            canvas: HalfblockCanvas::new(),
-            debug_line_y: 0,
+            canvas: TerminalCanvas::new(args),
+            heatmap_gradient: heatmap::heatmap_gradient(),
+            recorder: args.record.as_ref().map(|path| {
+                Recorder::create(path, args.record_simulation_only).unwrap_or_else(|error| {
+                    eprintln!("termgol: cannot record to {}: {error}", path.display());
+                    process::exit(1);
+                })
+            }),
+            text_color: args.theme.text_color(),
         }
     }
 
@@ -24,13 +51,75 @@ impl Renderer {
         self.canvas.resize()
     }
 
+    /// The size of the world in pixels, the status bar takes the last text
+    /// row if it is visible.
+    pub fn world_size(&self, state: &State) -> Point {
+        let size = self.canvas.get_size().clone();
+        if !state.status_bar_visible {
+            return size;
+        }
+
+        let status_bar = self
+            .canvas
+            .text_rect(0, self.canvas.text_size().height() - 1, 1, 1);
+        Point::new(size.width(), status_bar.pos.y.clamp(0, size.height()))
+    }
+
+    /// The last text row over the world.
+    fn bottom_text_row(&self, state: &State) -> i32 {
+        self.canvas.text_size().height() - 1 - state.status_bar_visible as i32
+    }
+
     pub fn display(&mut self, state: &State) {
+        // while going to a generation the last frame stays on the screen and
+        // only the progress message is updated
+        if state.goto_generation.is_some() {
+            self.draw_command_line(state);
+            self.show_frame(state);
+            return;
+        }
+
+        let visible = |color: &Rgba| {
+            Rgba {
+                a: state.world.color_alpha,
+                ..color.clone()
+            }
+            .blend(&Rgba::black())
+        };
+        self.canvas.set_palette(
+            &visible(&state.world.color_bg_dead),
+            &visible(&state.world.color_bg_alive),
+        );
         self.canvas.clear();
 
-        self.draw_world(state);
+        if state.heatmap_visible {
+            self.draw_heatmap(state);
+        } else {
+            self.draw_world(state);
+        }
+        self.draw_selection(state);
+        self.draw_graph(state);
+        self.draw_census(state);
         self.draw_debug_info(state);
         self.draw_cursor(state);
+        self.draw_sparkline(state);
+        self.draw_status_bar(state);
+        self.draw_key_help(state);
+        self.draw_command_line(state);
+        self.show_frame(state);
+    }
 
-        self.canvas.display();
+    fn show_frame(&mut self, state: &State) {
+        let frame = self.canvas.render();
+        if let Some(recorder) = &mut self.recorder {
+            if recorder
+                .record(state, &self.canvas.text_size(), &frame)
+                .is_err()
+            {
+                // the recording stops, the session goes on
+                self.recorder = None;
+            }
+        }
+        self.canvas.flush(&frame);
     }
 }
@@ -0,0 +1,208 @@
+use term2d::model::color::Color;
+use term2d::model::point::Point;
+use term2d::model::rect::Rect;
+use term2d::model::rgba::Rgba;
+use term2d::view::canvas::Canvas;
+use term2d::view::screen::RawTerminalScreen;
+
+use crate::common::args::Args;
+
+use self::ascii::AsciiCanvas;
+use self::braille::BrailleCanvas;
+use self::halfblock::HalfblockCanvas;
+
+pub mod ascii;
+pub mod braille;
+mod char_buffer;
+pub mod color_mode;
+mod halfblock;
+
+/// How the pixels of the world are mapped to terminal characters.
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum RenderMode {
+    /// 1x2 cells per character using half blocks, each with its own color
+    Halfblock,
+    /// 2x4 cells per character using braille patterns, with two colors per
+    /// character
+    Braille,
+    /// 1x1 cells per one or two plain characters without colors
+    Ascii,
+}
+
+/// The canvas of the selected render mode.
+pub enum TerminalCanvas {
+    Halfblock(HalfblockCanvas),
+    Braille(BrailleCanvas),
+    Ascii(AsciiCanvas),
+}
+
+impl TerminalCanvas {
+    pub fn new(args: &Args) -> Self {
+        let color_mode = args.color_mode.resolve();
+
+        match args.render_mode {
+            RenderMode::Halfblock => Self::Halfblock(HalfblockCanvas::new(color_mode)),
+            RenderMode::Braille => {
+                Self::Braille(BrailleCanvas::new(args.braille_coloring, color_mode))
+            }
+            RenderMode::Ascii => Self::Ascii(AsciiCanvas::new(&args.char_alive, &args.char_dead)),
+        }
+    }
+
+    /// The number of pixels and the number of characters which cover the
+    /// same area.
+    fn scale(&self) -> (Point, Point) {
+        match self {
+            Self::Halfblock(_) => (Point::new(1, 2), Point::new(1, 1)),
+            Self::Braille(_) => (Point::new(2, 4), Point::new(1, 1)),
+            Self::Ascii(canvas) => (Point::new(1, 1), Point::new(canvas.cell_width(), 1)),
+        }
+    }
+
+    /// Whether text may contain characters outside of ascii.
+    pub fn is_unicode(&self) -> bool {
+        !matches!(self, Self::Ascii(_))
+    }
+
+    /// The number of text columns and rows of the terminal.
+    pub fn text_size(&self) -> Point {
+        let (pixels, chars) = self.scale();
+        let size = self.get_size();
+        Point::new(
+            size.width() * chars.x / pixels.x,
+            size.height() * chars.y / pixels.y,
+        )
+    }
+
+    /// The pixels covered by a rectangle of text columns and rows.
+    pub fn text_rect(&self, column: i32, row: i32, width: i32, height: i32) -> Rect {
+        let (pixels, chars) = self.scale();
+        let x = column * pixels.x / chars.x;
+        let y = row * pixels.y / chars.y;
+        let x_end = ((column + width) * pixels.x + chars.x - 1) / chars.x;
+        let y_end = ((row + height) * pixels.y + chars.y - 1) / chars.y;
+        Rect::new(x, y, x_end - x, y_end - y)
+    }
+
+    /// Draws text at a text column and row instead of a pixel position.
+    pub fn draw_text_at(&mut self, column: i32, row: i32, color: &Color, text: &str) {
+        if let Self::Ascii(canvas) = self {
+            canvas.draw_text_at(column, row, text);
+            return;
+        }
+
+        let p = self.text_rect(column, row, 0, 0).pos;
+        self.draw_text(&p, color, text);
+    }
+
+    /// The escape sequences which show the drawn frame, as they are written
+    /// to the terminal.
+    pub fn render(&mut self) -> String {
+        match self {
+            Self::Halfblock(canvas) => canvas.render(),
+            Self::Braille(canvas) => canvas.render(),
+            Self::Ascii(canvas) => canvas.render(),
+        }
+    }
+
+    /// Writes a rendered frame to the terminal.
+    pub fn flush(&mut self, frame: &str) {
+        match self {
+            Self::Halfblock(canvas) => canvas.flush(frame),
+            Self::Braille(canvas) => canvas.flush(frame),
+            Self::Ascii(canvas) => canvas.flush(frame),
+        }
+    }
+
+    /// Sizes the canvas without a terminal so that it covers the pixels,
+    /// e.g. to measure rendering.
+    pub fn resize_offscreen(&mut self, pixels: &Point) {
+        let (pixel_scale, char_scale) = self.scale();
+        let screen_size = Point::new(
+            (pixels.width() * char_scale.x + pixel_scale.x - 1) / pixel_scale.x,
+            (pixels.height() * char_scale.y + pixel_scale.y - 1) / pixel_scale.y,
+        );
+
+        match self {
+            Self::Halfblock(canvas) => canvas.set_screen_size(&screen_size),
+            Self::Braille(canvas) => canvas.set_screen_size(&screen_size),
+            Self::Ascii(canvas) => canvas.set_screen_size(&screen_size),
+        }
+    }
+
+    /// Sets the colors used by canvases which can not show the color of
+    /// every pixel.
+    pub fn set_palette(&mut self, background: &Rgba, foreground: &Rgba) {
+        match self {
+            Self::Halfblock(_) => {}
+            Self::Braille(canvas) => canvas.set_palette(background, foreground),
+            Self::Ascii(canvas) => canvas.set_palette(background, foreground),
+        }
+    }
+}
+
+impl Canvas for TerminalCanvas {
+    fn init(&mut self, screen: RawTerminalScreen) {
+        match self {
+            Self::Halfblock(canvas) => canvas.init(screen),
+            Self::Braille(canvas) => canvas.init(screen),
+            Self::Ascii(canvas) => canvas.init(screen),
+        }
+    }
+
+    fn get_size(&self) -> &Point {
+        match self {
+            Self::Halfblock(canvas) => canvas.get_size(),
+            Self::Braille(canvas) => canvas.get_size(),
+            Self::Ascii(canvas) => canvas.get_size(),
+        }
+    }
+
+    fn resize(&mut self) -> &Point {
+        match self {
+            Self::Halfblock(canvas) => canvas.resize(),
+            Self::Braille(canvas) => canvas.resize(),
+            Self::Ascii(canvas) => canvas.resize(),
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            Self::Halfblock(canvas) => canvas.clear(),
+            Self::Braille(canvas) => canvas.clear(),
+            Self::Ascii(canvas) => canvas.clear(),
+        }
+    }
+
+    fn draw_pixel(&mut self, p: &Point, rgba: &Rgba) {
+        match self {
+            Self::Halfblock(canvas) => canvas.draw_pixel(p, rgba),
+            Self::Braille(canvas) => canvas.draw_pixel(p, rgba),
+            Self::Ascii(canvas) => canvas.draw_pixel(p, rgba),
+        }
+    }
+
+    fn draw_char(&mut self, p: &Point, color: &Color, ch: char) {
+        match self {
+            Self::Halfblock(canvas) => canvas.draw_char(p, color, ch),
+            Self::Braille(canvas) => canvas.draw_char(p, color, ch),
+            Self::Ascii(canvas) => canvas.draw_char(p, color, ch),
+        }
+    }
+
+    fn draw_text(&mut self, p: &Point, color: &Color, text: &str) {
+        match self {
+            Self::Halfblock(canvas) => canvas.draw_text(p, color, text),
+            Self::Braille(canvas) => canvas.draw_text(p, color, text),
+            Self::Ascii(canvas) => canvas.draw_text(p, color, text),
+        }
+    }
+
+    fn display(&mut self) {
+        match self {
+            Self::Halfblock(canvas) => canvas.display(),
+            Self::Braille(canvas) => canvas.display(),
+            Self::Ascii(canvas) => canvas.display(),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use clap::Command;
+use clap::Error;
+use clap::ErrorKind;
+use term2d::model::ansiesc::cursor_goto;
+use term2d::model::color::Color;
+use term2d::model::point::Point;
+use term2d::model::rect::Rect;
+use term2d::model::rgba::Rgba;
+use term2d::view::canvas::Canvas;
+use term2d::view::screen::RawTerminalScreen;
+use term2d::view::screen::Screen;
+
+/// Parses the characters of a cell, one or two characters for square looking
+/// cells.
+pub fn parse_cell_chars(s: &str) -> Result<String, Error> {
+    match s.chars().count() {
+        1 | 2 => Ok(s.to_string()),
+        _ => Err(Command::new("set argument to e.g. '#' or '[]'")
+            .error(ErrorKind::InvalidValue, "invalid cell characters")),
+    }
+}
+
+/// Repeats a single character to fill a cell of the specified width.
+fn fill_cell(chars: &str, width: usize) -> Vec<char> {
+    chars.chars().cycle().take(width).collect()
+}
+
+/// A canvas with one pixel per cell drawn as plain characters without any
+/// color escape sequences, so that it works without truecolor or unicode.
+/// A cell is alive when its color is closer to the alive than to the dead
+/// color.
+pub struct AsciiCanvas {
+    background: Rgba,
+    cell_width: usize,
+    char_alive: Vec<char>,
+    char_dead: Vec<char>,
+    foreground: Rgba,
+    pixels: Vec<Rgba>,
+    screen: Option<RawTerminalScreen>,
+    size: Point,
+    text: Vec<Option<char>>,
+}
+
+impl AsciiCanvas {
+    pub fn new(char_alive: &str, char_dead: &str) -> Self {
+        let cell_width = char_alive.chars().count().max(char_dead.chars().count());
+
+        Self {
+            background: Rgba::black(),
+            cell_width,
+            char_alive: fill_cell(char_alive, cell_width),
+            char_dead: fill_cell(char_dead, cell_width),
+            foreground: Rgba::white(),
+            pixels: Vec::new(),
+            screen: None,
+            size: Point::new(0, 0),
+            text: Vec::new(),
+        }
+    }
+
+    pub fn cell_width(&self) -> i32 {
+        self.cell_width as i32
+    }
+
+    /// Sets the dead and alive colors which pixels are compared to, takes
+    /// effect on the next clear.
+    pub fn set_palette(&mut self, background: &Rgba, foreground: &Rgba) {
+        self.background = background.clone();
+        self.foreground = foreground.clone();
+    }
+
+    /// Draws text at a character position, which can be in the middle of a
+    /// cell that is two characters wide.
+    pub fn draw_text_at(&mut self, column: i32, row: i32, text: &str) {
+        let columns = self.columns();
+        if row < 0 || row >= self.size.height() {
+            return;
+        }
+
+        for (i, ch) in text.chars().enumerate() {
+            let column = column + i as i32;
+            if column >= 0 && column < columns {
+                self.text[(columns * row + column) as usize] = Some(ch);
+            }
+        }
+    }
+
+    fn columns(&self) -> i32 {
+        self.size.width() * self.cell_width()
+    }
+
+    pub fn set_screen_size(&mut self, screen_size: &Point) {
+        self.size = Point::new(
+            screen_size.width() / self.cell_width(),
+            screen_size.height(),
+        );
+        self.clear_buffers();
+    }
+
+    /// The rows of characters, each preceded by moving the cursor to it.
+    pub fn render(&mut self) -> String {
+        let mut s = String::new();
+        for (row, line) in self.rows().iter().enumerate() {
+            s.push_str(&cursor_goto(1, row as i32 + 1));
+            s.push_str(line);
+        }
+
+        s
+    }
+
+    pub fn flush(&mut self, frame: &str) {
+        self.screen.as_mut().unwrap().flush_pixels(frame.as_bytes());
+    }
+
+    fn clear_buffers(&mut self) {
+        let pixels = (self.size.width() * self.size.height()) as usize;
+        self.pixels = vec![self.background.clone(); pixels];
+        self.text = vec![None; self.cell_width * pixels];
+    }
+
+    fn is_alive(&self, rgba: &Rgba) -> bool {
+        let distance = |other: &Rgba| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(rgba.r, other.r) + d(rgba.g, other.g) + d(rgba.b, other.b)
+        };
+        distance(&self.foreground) < distance(&self.background)
+    }
+
+    /// The character at a screen position without text.
+    fn cell_char(&self, column: i32, row: i32) -> char {
+        let x = column / self.cell_width();
+        let chars = if self.is_alive(&self.pixels[(self.size.width() * row + x) as usize]) {
+            &self.char_alive
+        } else {
+            &self.char_dead
+        };
+        chars[(column % self.cell_width()) as usize]
+    }
+
+    fn rows(&self) -> Vec<String> {
+        let columns = self.columns();
+
+        (0..self.size.height())
+            .map(|row| {
+                (0..columns)
+                    .map(|column| {
+                        self.text[(columns * row + column) as usize]
+                            .unwrap_or_else(|| self.cell_char(column, row))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Canvas for AsciiCanvas {
+    fn init(&mut self, screen: RawTerminalScreen) {
+        self.set_screen_size(&screen.get_size().clone());
+        self.screen = Some(screen);
+    }
+
+    fn get_size(&self) -> &Point {
+        &self.size
+    }
+
+    fn resize(&mut self) -> &Point {
+        let screen_size = self.screen.as_mut().unwrap().resize().clone();
+        self.set_screen_size(&screen_size);
+        &self.size
+    }
+
+    fn clear(&mut self) {
+        self.clear_buffers();
+    }
+
+    fn draw_pixel(&mut self, p: &Point, rgba: &Rgba) {
+        if !Rect::from(&self.size).contains(p) {
+            return;
+        }
+
+        let i = (self.size.width() * p.y + p.x) as usize;
+        self.pixels[i] = rgba.blend(&self.pixels[i]);
+    }
+
+    fn draw_char(&mut self, p: &Point, _color: &Color, ch: char) {
+        self.draw_text_at(p.x * self.cell_width(), p.y, &ch.to_string());
+    }
+
+    fn draw_text(&mut self, p: &Point, _color: &Color, text: &str) {
+        self.draw_text_at(p.x * self.cell_width(), p.y, text);
+    }
+
+    fn display(&mut self) {
+        let frame = self.render();
+        self.flush(&frame);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn canvas(char_alive: &str, char_dead: &str, screen_size: Point) -> AsciiCanvas {
+        let mut canvas = AsciiCanvas::new(char_alive, char_dead);
+        canvas.set_palette(&Rgba::black(), &Rgba::white());
+        canvas.set_screen_size(&screen_size);
+        canvas
+    }
+
+    #[test]
+    fn it_draws_cells_as_characters() {
+        let mut canvas = canvas("#", ".", Point::new(3, 2));
+        canvas.draw_pixel(&Point::new(1, 0), &Rgba::white());
+        canvas.draw_pixel(
+            &Point::new(2, 1),
+            &Rgba {
+                a: 100,
+                ..Rgba::white()
+            },
+        );
+        canvas.draw_text_at(0, 1, "x");
+
+        assert_eq!(canvas.get_size(), &Point::new(3, 2));
+        assert_eq!(canvas.rows(), vec![".#.", "x.."]);
+    }
+
+    #[test]
+    fn it_draws_cells_two_characters_wide() {
+        let mut canvas = canvas("[]", ".", Point::new(7, 1));
+        canvas.draw_pixel(&Point::new(0, 0), &Rgba::white());
+        canvas.draw_text_at(5, 0, "x");
+
+        assert_eq!(canvas.get_size(), &Point::new(3, 1));
+        assert_eq!(canvas.rows(), vec!["[]...x"]);
+    }
+
+    #[test]
+    fn it_fails_when_the_cell_characters_are_invalid() {
+        assert!(parse_cell_chars("").is_err());
+        assert!(parse_cell_chars("[-]").is_err());
+        assert_eq!(parse_cell_chars("[]").unwrap(), "[]");
+    }
+}
@@ -0,0 +1,258 @@
+use term2d::model::color::Color;
+use term2d::model::point::Point;
+use term2d::model::rect::Rect;
+use term2d::model::rgba::Rgba;
+use term2d::view::canvas::Canvas;
+use term2d::view::screen::RawTerminalScreen;
+use term2d::view::screen::Screen;
+
+use super::char_buffer::CharBuffer;
+use super::color_mode::ColorMode;
+
+const BRAILLE_BLANK: u32 = 0x2800;
+/// The bits of the dots in a braille character, indexed by `[x][y]`.
+const BRAILLE_DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+/// Dots whose colors differ less than this in every channel from the
+/// background are not set.
+const BRAILLE_THRESHOLD: u8 = 32;
+
+/// How the two colors of a braille character are chosen from its 8 dots.
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum BrailleColoring {
+    /// The most common colors of the set and unset dots
+    Majority,
+    /// The foreground color for all set dots on the background color
+    Single,
+}
+
+/// A canvas with 2x4 pixels per character using the unicode braille
+/// patterns. Every pixel is a dot which is either set or unset, so each
+/// character can only show a foreground and a background color.
+pub struct BrailleCanvas {
+    background: Rgba,
+    buffer: CharBuffer,
+    color_mode: ColorMode,
+    coloring: BrailleColoring,
+    dots: Vec<Rgba>,
+    foreground: Rgba,
+    screen: Option<RawTerminalScreen>,
+    size: Point,
+    text: Vec<Option<(char, Color)>>,
+}
+
+impl BrailleCanvas {
+    pub fn new(coloring: BrailleColoring, color_mode: ColorMode) -> Self {
+        Self {
+            background: Rgba::black(),
+            buffer: CharBuffer::new(&Point::new(0, 0)),
+            color_mode,
+            coloring,
+            dots: Vec::new(),
+            foreground: Rgba::white(),
+            screen: None,
+            size: Point::new(0, 0),
+            text: Vec::new(),
+        }
+    }
+
+    /// Sets the background which unset dots are compared to and the
+    /// foreground of the single coloring, takes effect on the next clear.
+    pub fn set_palette(&mut self, background: &Rgba, foreground: &Rgba) {
+        self.background = background.clone();
+        self.foreground = foreground.clone();
+    }
+
+    pub fn set_screen_size(&mut self, screen_size: &Point) {
+        self.buffer = CharBuffer::new(screen_size);
+        self.size = Point::new(2 * screen_size.width(), 4 * screen_size.height());
+        self.clear_buffers();
+    }
+
+    /// The escape sequences which show the drawn frame, text is drawn over
+    /// the braille characters.
+    pub fn render(&mut self) -> String {
+        let columns = self.size.width() / 2;
+        let rows = self.size.height() / 4;
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let p = Point::new(column, row);
+                let (ch, color) = self.braille_char(column, row);
+                let text = self.text[(columns * row + column) as usize].take();
+                self.buffer.draw_char(&p, &color, ch);
+                if let Some((ch, color)) = text {
+                    self.buffer.draw_char(&p, &color, ch);
+                }
+            }
+        }
+
+        self.buffer.to_ansi(self.color_mode)
+    }
+
+    pub fn flush(&mut self, frame: &str) {
+        self.screen.as_mut().unwrap().flush_pixels(frame.as_bytes());
+    }
+
+    fn clear_buffers(&mut self) {
+        self.dots =
+            vec![self.background.clone(); (self.size.width() * self.size.height()) as usize];
+        self.text = vec![None; (self.size.width() / 2 * self.size.height() / 4) as usize];
+    }
+
+    fn is_set(&self, rgba: &Rgba) -> bool {
+        let differs = |a: u8, b: u8| a.abs_diff(b) >= BRAILLE_THRESHOLD;
+        differs(rgba.r, self.background.r)
+            || differs(rgba.g, self.background.g)
+            || differs(rgba.b, self.background.b)
+    }
+
+    /// Computes the character and its colors for the 2x4 dots at the
+    /// specified character position.
+    fn braille_char(&self, column: i32, row: i32) -> (char, Color) {
+        let mut bits = 0;
+        let mut set = Vec::with_capacity(8);
+        let mut unset = Vec::with_capacity(8);
+
+        for (dx, column_bits) in BRAILLE_DOTS.iter().enumerate() {
+            for (dy, bit) in column_bits.iter().enumerate() {
+                let p = Point::new(2 * column + dx as i32, 4 * row + dy as i32);
+                let dot = &self.dots[(self.size.width() * p.y + p.x) as usize];
+                if self.is_set(dot) {
+                    bits |= bit;
+                    set.push(dot);
+                } else {
+                    unset.push(dot);
+                }
+            }
+        }
+
+        let color = match self.coloring {
+            BrailleColoring::Majority => Color {
+                fg: majority(&set).unwrap_or_else(|| self.foreground.clone()),
+                bg: majority(&unset).unwrap_or_else(|| self.background.clone()),
+            },
+            BrailleColoring::Single => Color {
+                fg: self.foreground.clone(),
+                bg: self.background.clone(),
+            },
+        };
+
+        (char::from_u32(BRAILLE_BLANK + bits).unwrap(), color)
+    }
+}
+
+/// The most common color, the first one wins a tie.
+fn majority(colors: &[&Rgba]) -> Option<Rgba> {
+    colors
+        .iter()
+        .max_by_key(|&&color| {
+            let count = colors.iter().filter(|&&other| other == color).count();
+            (
+                count,
+                std::cmp::Reverse(colors.iter().position(|c| c == &color)),
+            )
+        })
+        .map(|&color| color.clone())
+}
+
+impl Canvas for BrailleCanvas {
+    fn init(&mut self, screen: RawTerminalScreen) {
+        self.set_screen_size(&screen.get_size().clone());
+        self.screen = Some(screen);
+    }
+
+    fn get_size(&self) -> &Point {
+        &self.size
+    }
+
+    fn resize(&mut self) -> &Point {
+        let screen_size = self.screen.as_mut().unwrap().resize().clone();
+        self.set_screen_size(&screen_size);
+        &self.size
+    }
+
+    fn clear(&mut self) {
+        self.buffer.clear();
+        self.clear_buffers();
+    }
+
+    fn draw_pixel(&mut self, p: &Point, rgba: &Rgba) {
+        if !Rect::from(&self.size).contains(p) {
+            return;
+        }
+
+        let i = (self.size.width() * p.y + p.x) as usize;
+        self.dots[i] = rgba.blend(&self.dots[i]);
+    }
+
+    fn draw_char(&mut self, p: &Point, color: &Color, ch: char) {
+        let columns = self.size.width() / 2;
+        let (column, row) = (p.x / 2, p.y / 4);
+        if column < 0 || column >= columns || row < 0 || row >= self.size.height() / 4 {
+            return;
+        }
+
+        self.text[(columns * row + column) as usize] = Some((ch, color.clone()));
+    }
+
+    fn draw_text(&mut self, p: &Point, color: &Color, text: &str) {
+        for (i, ch) in text.chars().enumerate() {
+            self.draw_char(&Point::new(p.x + 2 * i as i32, p.y), color, ch);
+        }
+    }
+
+    fn display(&mut self) {
+        let frame = self.render();
+        self.flush(&frame);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn canvas(coloring: BrailleColoring) -> BrailleCanvas {
+        let mut canvas = BrailleCanvas::new(coloring, ColorMode::Truecolor);
+        canvas.set_screen_size(&Point::new(1, 1));
+        canvas
+    }
+
+    #[test]
+    fn it_maps_pixels_to_braille_dots() {
+        let mut canvas = canvas(BrailleColoring::Majority);
+        canvas.draw_pixel(&Point::new(0, 0), &Rgba::white());
+        canvas.draw_pixel(&Point::new(1, 3), &Rgba::white());
+        assert_eq!(canvas.braille_char(0, 0).0, '⢁');
+
+        for y in 0..4 {
+            canvas.draw_pixel(&Point::new(0, y), &Rgba::white());
+            canvas.draw_pixel(&Point::new(1, y), &Rgba::white());
+        }
+        assert_eq!(canvas.braille_char(0, 0).0, '⣿');
+    }
+
+    #[test]
+    fn it_colors_characters_by_the_majority_of_their_dots() {
+        let mut canvas = canvas(BrailleColoring::Majority);
+        canvas.draw_pixel(&Point::new(0, 0), &Rgba::red());
+        canvas.draw_pixel(&Point::new(0, 1), &Rgba::green());
+        canvas.draw_pixel(&Point::new(0, 2), &Rgba::green());
+
+        let (_, color) = canvas.braille_char(0, 0);
+        assert_eq!(color.fg, Rgba::green());
+        assert_eq!(color.bg, Rgba::black());
+    }
+
+    #[test]
+    fn it_colors_characters_with_a_single_foreground() {
+        let mut canvas = BrailleCanvas::new(BrailleColoring::Single, ColorMode::Truecolor);
+        canvas.set_palette(&Rgba::blue(), &Rgba::yellow());
+        canvas.set_screen_size(&Point::new(1, 1));
+        canvas.draw_pixel(&Point::new(0, 0), &Rgba::red());
+
+        let (ch, color) = canvas.braille_char(0, 0);
+        assert_eq!(ch, '⠁');
+        assert_eq!(color.fg, Rgba::yellow());
+        assert_eq!(color.bg, Rgba::blue());
+    }
+}
@@ -0,0 +1,134 @@
+use term2d::model::ansiesc::cursor_goto;
+use term2d::model::ansiesc::COLOR_RESET;
+use term2d::model::color::Color;
+use term2d::model::point::Point;
+use term2d::model::rect::Rect;
+use term2d::view::screen::Pixel;
+
+use super::color_mode::is_bright;
+use super::color_mode::ColorMode;
+
+pub const HALF_BLOCK: char = '▀';
+
+/// The characters and colors of the terminal, written with the escape
+/// sequences of a color mode.
+pub struct CharBuffer {
+    pixels: Vec<Pixel>,
+    size: Point,
+}
+
+impl CharBuffer {
+    pub fn new(size: &Point) -> Self {
+        Self {
+            pixels: vec![Pixel::default(); (size.width() * size.height()) as usize],
+            size: size.clone(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pixels.fill(Pixel::default());
+    }
+
+    pub fn get_color(&self, p: &Point) -> Color {
+        self.pixels[(self.size.width() * p.y + p.x) as usize]
+            .color
+            .clone()
+    }
+
+    /// Draws a character, its colors are blended over the current colors.
+    pub fn draw_char(&mut self, p: &Point, color: &Color, ch: char) {
+        if !Rect::from(&self.size).contains(p) {
+            return;
+        }
+
+        let pixel = &mut self.pixels[(self.size.width() * p.y + p.x) as usize];
+        pixel.color = Color {
+            bg: color.bg.blend(&pixel.color.bg),
+            fg: color.fg.blend(&pixel.color.fg),
+        };
+        pixel.ch = ch;
+    }
+
+    /// Writes all rows, colors are only set when they change. Monochrome
+    /// has no colors, so half blocks are replaced by the block which shows
+    /// their bright halves.
+    pub fn to_ansi(&self, color_mode: ColorMode) -> String {
+        let mut s = String::from(COLOR_RESET);
+
+        for (row, pixels) in self.pixels.chunks(self.size.width() as usize).enumerate() {
+            s.push_str(&cursor_goto(1, row as i32 + 1));
+
+            let mut last_color: Option<&Color> = None;
+            for pixel in pixels {
+                if color_mode == ColorMode::Mono {
+                    s.push(mono_char(pixel));
+                    continue;
+                }
+
+                if last_color.is_none_or(|last| last.fg != pixel.color.fg) {
+                    s.push_str(&color_mode.fg_ansi(&pixel.color.fg));
+                }
+                if last_color.is_none_or(|last| last.bg != pixel.color.bg) {
+                    s.push_str(&color_mode.bg_ansi(&pixel.color.bg));
+                }
+                last_color = Some(&pixel.color);
+                s.push(pixel.ch);
+            }
+        }
+
+        s
+    }
+}
+
+fn mono_char(pixel: &Pixel) -> char {
+    if pixel.ch != HALF_BLOCK {
+        return pixel.ch;
+    }
+
+    match (is_bright(&pixel.color.fg), is_bright(&pixel.color.bg)) {
+        (true, true) => '█',
+        (true, false) => '▀',
+        (false, true) => '▄',
+        (false, false) => ' ',
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use term2d::model::rgba::Rgba;
+
+    use super::*;
+
+    #[test]
+    fn it_only_writes_changed_colors() {
+        let mut buffer = CharBuffer::new(&Point::new(3, 1));
+        let color = Color {
+            bg: Rgba::black(),
+            fg: Rgba::red(),
+        };
+        buffer.draw_char(&Point::new(0, 0), &color, 'a');
+        buffer.draw_char(&Point::new(1, 0), &color, 'b');
+
+        assert_eq!(
+            buffer.to_ansi(ColorMode::Ansi16),
+            "\x1b[0m\x1b[1;1H\x1b[91m\x1b[40mab\x1b[30m "
+        );
+    }
+
+    #[test]
+    fn it_writes_half_blocks_in_monochrome() {
+        let mut buffer = CharBuffer::new(&Point::new(2, 1));
+        let top = Color {
+            bg: Rgba::black(),
+            fg: Rgba::white(),
+        };
+        let bottom = Color {
+            bg: Rgba::white(),
+            fg: Rgba::black(),
+        };
+        buffer.draw_char(&Point::new(0, 0), &top, HALF_BLOCK);
+        buffer.draw_char(&Point::new(1, 0), &bottom, HALF_BLOCK);
+
+        assert_eq!(buffer.to_ansi(ColorMode::Mono), "\x1b[0m\x1b[1;1H▀▄");
+    }
+}
@@ -0,0 +1,181 @@
+use std::env;
+
+use term2d::model::rgba::Rgba;
+
+/// The intensities of the 6x6x6 color cube of the 256 color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The 16 standard colors as xterm shows them by default.
+const ANSI16_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The colors a terminal can show, every color is quantized to the nearest
+/// one of its palette when it is written.
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum ColorMode {
+    /// Detect the color support from the COLORTERM and TERM variables
+    Auto,
+    /// 24 bit colors
+    Truecolor,
+    /// The 256 color palette of xterm
+    #[clap(name = "256")]
+    Ansi256,
+    /// The 16 standard colors
+    #[clap(name = "16")]
+    Ansi16,
+    /// No colors, cells are either bright or dark
+    Mono,
+}
+
+impl ColorMode {
+    /// Replaces auto by the color mode detected from the environment.
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Auto => Self::detect(
+                env::var("COLORTERM").ok().as_deref(),
+                env::var("TERM").ok().as_deref(),
+            ),
+            mode => mode,
+        }
+    }
+
+    fn detect(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return Self::Truecolor;
+        }
+
+        match term {
+            Some(term) if term.contains("direct") => Self::Truecolor,
+            Some(term) if term.contains("256color") => Self::Ansi256,
+            Some("dumb") | None => Self::Mono,
+            Some(_) => Self::Ansi16,
+        }
+    }
+
+    /// The escape sequence which sets the foreground color.
+    pub fn fg_ansi(&self, rgba: &Rgba) -> String {
+        match self {
+            Self::Auto | Self::Truecolor => rgba.fg_ansi(),
+            Self::Ansi256 => format!("\x1b[38;5;{}m", ansi256_index(rgba)),
+            Self::Ansi16 => match ansi16_index(rgba) {
+                i @ 0..=7 => format!("\x1b[{}m", 30 + i),
+                i => format!("\x1b[{}m", 90 + i - 8),
+            },
+            Self::Mono => String::new(),
+        }
+    }
+
+    /// The escape sequence which sets the background color.
+    pub fn bg_ansi(&self, rgba: &Rgba) -> String {
+        match self {
+            Self::Auto | Self::Truecolor => rgba.bg_ansi(),
+            Self::Ansi256 => format!("\x1b[48;5;{}m", ansi256_index(rgba)),
+            Self::Ansi16 => match ansi16_index(rgba) {
+                i @ 0..=7 => format!("\x1b[{}m", 40 + i),
+                i => format!("\x1b[{}m", 100 + i - 8),
+            },
+            Self::Mono => String::new(),
+        }
+    }
+}
+
+fn distance(rgba: &Rgba, (r, g, b): (u8, u8, u8)) -> i32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+    d(rgba.r, r) + d(rgba.g, g) + d(rgba.b, b)
+}
+
+/// Whether a color is shown bright in monochrome.
+pub fn is_bright(rgba: &Rgba) -> bool {
+    299 * rgba.r as u32 + 587 * rgba.g as u32 + 114 * rgba.b as u32 >= 128_000
+}
+
+/// The nearest color of the 6x6x6 cube or the gray ramp.
+fn ansi256_index(rgba: &Rgba) -> u8 {
+    let level = |c: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - c as i32).abs())
+            .unwrap()
+    };
+    let (r, g, b) = (level(rgba.r), level(rgba.g), level(rgba.b));
+    let cube = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    let average = (rgba.r as i32 + rgba.g as i32 + rgba.b as i32) / 3;
+    let gray = ((average - 8).max(0) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * gray;
+
+    if distance(rgba, (gray_level, gray_level, gray_level)) < distance(rgba, cube) {
+        232 + gray
+    } else {
+        (16 + 36 * r + 6 * g + b) as u8
+    }
+}
+
+fn ansi16_index(rgba: &Rgba) -> u8 {
+    (0..ANSI16_COLORS.len())
+        .min_by_key(|&i| distance(rgba, ANSI16_COLORS[i]))
+        .unwrap() as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_detects_the_color_mode() {
+        assert_eq!(
+            ColorMode::detect(Some("truecolor"), Some("xterm-256color")),
+            ColorMode::Truecolor
+        );
+        assert_eq!(
+            ColorMode::detect(None, Some("screen-256color")),
+            ColorMode::Ansi256
+        );
+        assert_eq!(ColorMode::detect(None, Some("linux")), ColorMode::Ansi16);
+        assert_eq!(ColorMode::detect(None, Some("dumb")), ColorMode::Mono);
+    }
+
+    #[test]
+    fn it_quantizes_to_the_256_color_palette() {
+        assert_eq!(ansi256_index(&Rgba::red()), 196);
+        assert_eq!(ansi256_index(&Rgba::white()), 231);
+        let gray = Rgba {
+            r: 100,
+            g: 100,
+            b: 100,
+            a: 255,
+        };
+        assert_eq!(ansi256_index(&gray), 241);
+        assert_eq!(ColorMode::Ansi256.fg_ansi(&Rgba::red()), "\x1b[38;5;196m");
+    }
+
+    #[test]
+    fn it_quantizes_to_the_16_standard_colors() {
+        let orange = Rgba {
+            r: 238,
+            g: 136,
+            b: 34,
+            a: 255,
+        };
+        assert_eq!(ColorMode::Ansi16.bg_ansi(&Rgba::black()), "\x1b[40m");
+        assert_eq!(ColorMode::Ansi16.fg_ansi(&Rgba::white()), "\x1b[97m");
+        assert_eq!(ColorMode::Ansi16.fg_ansi(&orange), "\x1b[33m");
+        assert!(is_bright(&orange));
+        assert_eq!(ColorMode::Mono.fg_ansi(&orange), "");
+    }
+}
@@ -0,0 +1,103 @@
+use term2d::model::color::Color;
+use term2d::model::point::Point;
+use term2d::model::rect::Rect;
+use term2d::model::rgba::Rgba;
+use term2d::view::canvas::Canvas;
+use term2d::view::screen::RawTerminalScreen;
+use term2d::view::screen::Screen;
+
+use super::char_buffer::CharBuffer;
+use super::char_buffer::HALF_BLOCK;
+use super::color_mode::ColorMode;
+
+/// A canvas with 1x2 pixels per character, the upper pixel is the
+/// foreground and the lower pixel the background of a half block.
+pub struct HalfblockCanvas {
+    buffer: CharBuffer,
+    color_mode: ColorMode,
+    screen: Option<RawTerminalScreen>,
+    size: Point,
+}
+
+impl HalfblockCanvas {
+    pub fn new(color_mode: ColorMode) -> Self {
+        Self {
+            buffer: CharBuffer::new(&Point::new(0, 0)),
+            color_mode,
+            screen: None,
+            size: Point::new(0, 0),
+        }
+    }
+
+    /// The escape sequences which show the drawn frame.
+    pub fn render(&mut self) -> String {
+        self.buffer.to_ansi(self.color_mode)
+    }
+
+    pub fn flush(&mut self, frame: &str) {
+        self.screen.as_mut().unwrap().flush_pixels(frame.as_bytes());
+    }
+
+    pub fn set_screen_size(&mut self, screen_size: &Point) {
+        self.buffer = CharBuffer::new(screen_size);
+        self.size = Point::new(screen_size.width(), 2 * screen_size.height());
+    }
+}
+
+impl Canvas for HalfblockCanvas {
+    fn init(&mut self, screen: RawTerminalScreen) {
+        self.set_screen_size(&screen.get_size().clone());
+        self.screen = Some(screen);
+    }
+
+    fn get_size(&self) -> &Point {
+        &self.size
+    }
+
+    fn resize(&mut self) -> &Point {
+        let screen_size = self.screen.as_mut().unwrap().resize().clone();
+        self.set_screen_size(&screen_size);
+        &self.size
+    }
+
+    fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    fn draw_pixel(&mut self, p: &Point, rgba: &Rgba) {
+        if !Rect::from(&self.size).contains(p) {
+            return;
+        }
+
+        let q = Point::new(p.x, p.y / 2);
+        let old_color = self.buffer.get_color(&q);
+        let new_color = if p.y % 2 == 0 {
+            Color {
+                bg: old_color.bg,
+                fg: rgba.clone(),
+            }
+        } else {
+            Color {
+                bg: rgba.clone(),
+                fg: old_color.fg,
+            }
+        };
+
+        self.buffer.draw_char(&q, &new_color, HALF_BLOCK);
+    }
+
+    fn draw_char(&mut self, p: &Point, color: &Color, ch: char) {
+        self.buffer.draw_char(&Point::new(p.x, p.y / 2), color, ch);
+    }
+
+    fn draw_text(&mut self, p: &Point, color: &Color, text: &str) {
+        for (i, ch) in text.chars().enumerate() {
+            self.draw_char(&Point::new(p.x + i as i32, p.y), color, ch);
+        }
+    }
+
+    fn display(&mut self) {
+        let frame = self.render();
+        self.flush(&frame);
+    }
+}
@@ -0,0 +1,48 @@
+use crate::state::State;
+use term2d::model::rgba::Rgba;
+use term2d::view::canvas::Canvas;
+
+use super::Renderer;
+
+const CENSUS_BACKGROUND: Rgba = Rgba {
+    r: 0,
+    g: 0,
+    b: 0,
+    a: 160,
+};
+
+impl Renderer {
+    /// Draws the census in the top right corner, one text row per kind of
+    /// object.
+    pub fn draw_census(&mut self, state: &State) {
+        let Some(census) = &state.census else {
+            return;
+        };
+
+        let text_size = self.canvas.text_size();
+        let rows = (text_size.height() - 1).max(2) as usize;
+
+        let mut lines = vec![format!("census of generation {}", census.generation)];
+        lines.extend(census.lines().into_iter().take(rows - 1));
+        if census.entries.len() > rows - 1 {
+            lines.pop();
+            lines.push(format!("... {} more", census.entries.len() - rows + 2));
+        }
+
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as i32;
+        let column = (text_size.width() - width).max(0);
+
+        self.canvas.draw_rect_fill(
+            &self.canvas.text_rect(column, 0, width, lines.len() as i32),
+            &CENSUS_BACKGROUND,
+        );
+        for (row, line) in lines.iter().enumerate() {
+            self.canvas
+                .draw_text_at(column, row as i32, &self.text_color, line);
+        }
+    }
+}
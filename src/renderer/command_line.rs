@@ -0,0 +1,29 @@
+use crate::state::State;
+
+use super::Renderer;
+
+impl Renderer {
+    pub fn draw_command_line(&mut self, state: &State) {
+        let text = if let Some(command_line) = &state.command_line {
+            format!(":{command_line}_")
+        } else if let Some(message) = &state.message {
+            message.clone()
+        } else {
+            return;
+        };
+
+        // covers the status bar completely
+        let text_size = self.canvas.text_size();
+        let width = if state.status_bar_visible {
+            text_size.width().max(0) as usize
+        } else {
+            0
+        };
+        self.canvas.draw_text_at(
+            0,
+            text_size.height() - 1,
+            &self.text_color,
+            &format!("{text:<width$}"),
+        );
+    }
+}
@@ -1,6 +1,4 @@
 use crate::state::State;
-use term2d::model::point::Point;
-use term2d::model::rgba::Rgba;
 use term2d::view::canvas::Canvas;
 
 use super::Renderer;
@@ -11,16 +9,8 @@ impl Renderer {
             return;
         }
 
-        let position = Point::from(state.cursor_pos.clone());
+        let position = state.cursor_pos.clone();
 
-        self.canvas.draw_pixel(
-            &position,
-            &Rgba {
-                r: 255,
-                g: 255,
-                b: 255,
-                a: 255,
-            },
-        );
+        self.canvas.draw_pixel(&position, &state.args.theme.cursor);
     }
 }
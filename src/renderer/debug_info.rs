@@ -1,50 +1,192 @@
 use super::Renderer;
-use crate::common::DEBUG_INFO_PAGE_TOTAL;
+use crate::common::color::RgbaParser;
 use crate::state::State;
-use term2d::model::color::Color;
-use term2d::model::point::Point;
-use term2d::view::canvas::Canvas;
 
-impl Renderer {
-    pub fn draw_debug_info(&mut self, state: &State) {
-        match state.debug_info_page {
-            0 => (),
-            1 => self.draw_debug_info_general(state),
-            _ => panic!("debug info page {} out of bounds", state.debug_info_page),
-        }
-    }
+/// A page of debug info, its title and a function which writes its lines.
+type DebugPage = (&'static str, fn(&State) -> Vec<String>);
 
-    fn draw_next_line(&mut self, formatted_string: String) {
-        let color = Color::text();
+/// The pages `d` cycles through, the debug info is hidden after the last
+/// one.
+const DEBUG_PAGES: &[DebugPage] = &[
+    ("General", general),
+    ("Timing", timing),
+    ("Statistics", statistics),
+    ("Rules", rules),
+    ("Colors", colors),
+    ("Input", input),
+];
 
-        self.canvas
-            .draw_text(&Point::new(0, self.debug_line_y), &color, &formatted_string);
-        self.debug_line_y += 1;
+impl Renderer {
+    pub fn debug_info_page_count() -> usize {
+        DEBUG_PAGES.len()
     }
 
-    fn draw_page_info(&mut self, state: &State, text: &str) {
-        self.debug_line_y = 0;
+    pub fn draw_debug_info(&mut self, state: &State) {
+        let Some((title, lines)) = state
+            .debug_info_page
+            .checked_sub(1)
+            .and_then(|i| DEBUG_PAGES.get(i))
+        else {
+            return;
+        };
 
-        self.draw_next_line(format!(
-            //"\x1b[1m{}/{} {}\x1b[22m",
-            "{}/{} {}",
-            state.debug_info_page, DEBUG_INFO_PAGE_TOTAL, text,
-        ));
+        let header = format!("{}/{} {}", state.debug_info_page, DEBUG_PAGES.len(), title);
+        for (row, line) in [header].into_iter().chain(lines(state)).enumerate() {
+            self.canvas
+                .draw_text_at(0, row as i32, &self.text_color, &line);
+        }
     }
+}
 
-    fn draw_debug_info_general(&mut self, state: &State) {
-        self.draw_page_info(state, "General");
-
-        self.draw_next_line(format!(
-            "cols: {}, rows: {}, time: {}",
+fn general(state: &State) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "cols: {}, rows: {}, time: {}, generation: {}",
             state.world.size.width(),
             state.world.size.height(),
             state.elapsed_time,
-        ));
-
-        self.draw_next_line(format!(
+            state.world.generation,
+        ),
+        format!(
             "cursor_x: {}, cursor_y: {}",
             state.cursor_pos.x, state.cursor_pos.y,
-        ));
+        ),
+        format!("seed: {}", state.world.seed),
+    ];
+
+    if let Some(identification) = &state.identification {
+        lines.push(format!("identified: {identification}"));
+    }
+
+    lines
+}
+
+fn timing(state: &State) -> Vec<String> {
+    let timing = &state.timing;
+
+    vec![
+        format!(
+            "fps: {:.1} (target {})",
+            state.actual_frames_per_second, state.args.frames_per_second,
+        ),
+        format!("update: {:.2} ms per frame", timing.update_ms),
+        format!("render: {:.2} ms per frame", timing.render_ms),
+        format!("frame jitter: {:.2} ms", timing.jitter_ms),
+    ]
+}
+
+fn statistics(state: &State) -> Vec<String> {
+    let statistics = &state.world.statistics;
+    let detector = &state.period_detector;
+
+    vec![
+        format!(
+            "generation: {}, population: {}",
+            state.world.generation, statistics.population,
+        ),
+        format!(
+            "births: {}, deaths: {}",
+            statistics.births, statistics.deaths,
+        ),
+        match &statistics.bounding_box {
+            Some(bounding_box) => format!(
+                "bounding box: {}x{} at ({}, {})",
+                bounding_box.size.width(),
+                bounding_box.size.height(),
+                bounding_box.pos.x,
+                bounding_box.pos.y,
+            ),
+            None => "bounding box: -".to_string(),
+        },
+        format!(
+            "density: {:.2}%",
+            100.0 * statistics.density(&state.world.size),
+        ),
+        match (detector.period, detector.stable_since) {
+            (Some(1), Some(since)) => format!("stabilized: static since generation {since}"),
+            (Some(period), Some(since)) => {
+                format!("stabilized: period {period} since generation {since}")
+            }
+            _ => "stabilized: no".to_string(),
+        },
+    ]
+}
+
+fn rules(state: &State) -> Vec<String> {
+    let rules = &state.world.rules;
+    let digits = |neighbors: &[u32]| {
+        let digits: Vec<String> = neighbors.iter().map(|n| n.to_string()).collect();
+        if digits.is_empty() {
+            "none".to_string()
+        } else {
+            digits.join(", ")
+        }
+    };
+
+    vec![
+        format!("rules: {rules} ({})", rules.name().unwrap_or("custom")),
+        format!("birth with neighbors: {}", digits(&rules.birth)),
+        format!("survival with neighbors: {}", digits(&rules.survival)),
+        format!("fading speed: {}", state.world.fading_speed),
+    ]
+}
+
+fn colors(state: &State) -> Vec<String> {
+    let world = &state.world;
+    let args = &state.args;
+
+    vec![
+        format!("theme: {}", args.theme),
+        format!(
+            "render mode: {:?}, color mode: {:?}",
+            args.render_mode, args.color_mode,
+        ),
+        format!(
+            "alive: {}, dead: {}, alpha: {}",
+            RgbaParser::format(&world.color_bg_alive),
+            RgbaParser::format(&world.color_bg_dead),
+            world.color_alpha,
+        ),
+        match &world.rainbow {
+            Some(rainbow) => format!("rainbow: {rainbow}"),
+            None => "rainbow: off".to_string(),
+        },
+        if world.color_by_age {
+            format!("age gradient: {}", world.age_gradient)
+        } else {
+            "age gradient: off".to_string()
+        },
+    ]
+}
+
+fn input(state: &State) -> Vec<String> {
+    let keys: Vec<&str> = state.input_events.iter().map(String::as_str).collect();
+
+    vec![format!("last keys: {}", keys.join(" "))]
+}
+
+#[cfg(test)]
+mod test {
+    use term2d::model::key::Key;
+    use term2d::model::point::Point;
+
+    use super::*;
+    use crate::common::args::Args;
+
+    #[test]
+    fn it_cycles_through_all_pages_and_hides_after_the_last() {
+        let mut state = State::from(Args::default());
+        state.resize(&Point::new(20, 10));
+        state.record_key(&Key::Char('d'));
+
+        for (page, (_, lines)) in DEBUG_PAGES.iter().enumerate() {
+            state.debug_info_next_page(DEBUG_PAGES.len());
+            assert_eq!(state.debug_info_page, page + 1);
+            assert!(!lines(&state).is_empty());
+        }
+
+        state.debug_info_next_page(DEBUG_PAGES.len());
+        assert_eq!(state.debug_info_page, 0);
+        assert_eq!(input(&state), ["last keys: d"]);
     }
 }
@@ -0,0 +1,175 @@
+use crate::state::history::Sample;
+use crate::state::State;
+use term2d::model::point::Point;
+use term2d::model::rect::Rect;
+use term2d::model::rgba::Rgba;
+use term2d::view::canvas::Canvas;
+
+use super::Renderer;
+
+const SPARKLINE_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const SPARKLINE_ASCII_CHARS: [char; 8] = ['_', '.', ',', ':', '-', '=', '+', '#'];
+
+const GRAPH_BACKGROUND: Rgba = Rgba {
+    r: 0,
+    g: 0,
+    b: 0,
+    a: 160,
+};
+const GRAPH_BIRTHS: Rgba = Rgba {
+    r: 80,
+    g: 220,
+    b: 80,
+    a: 255,
+};
+const GRAPH_DEATHS: Rgba = Rgba {
+    r: 220,
+    g: 80,
+    b: 80,
+    a: 255,
+};
+const GRAPH_POPULATION: Rgba = Rgba {
+    r: 200,
+    g: 200,
+    b: 200,
+    a: 255,
+};
+
+/// Maps a value to the range 0..=1 relative to the maximum.
+fn scale(value: usize, maximum: usize, logarithmic: bool) -> f32 {
+    if maximum == 0 {
+        return 0.0;
+    }
+
+    if logarithmic {
+        (value as f32).ln_1p() / (maximum as f32).ln_1p()
+    } else {
+        value as f32 / maximum as f32
+    }
+}
+
+pub fn sparkline(values: &[usize], logarithmic: bool, chars: &[char; 8]) -> String {
+    let maximum = values.iter().copied().max().unwrap_or(0);
+    let top = chars.len() - 1;
+
+    values
+        .iter()
+        .map(|&value| {
+            let i = (scale(value, maximum, logarithmic) * top as f32).round() as usize;
+            chars[i.min(top)]
+        })
+        .collect()
+}
+
+impl Renderer {
+    pub fn draw_graph(&mut self, state: &State) {
+        if !state.graph_visible {
+            return;
+        }
+
+        let size = self.world_size(state);
+        let bottom = if state.sparkline_visible {
+            let row = self.sparkline_row(state);
+            self.canvas.text_rect(0, row, 1, 1).pos.y
+        } else {
+            size.height()
+        };
+        let height = state.graph_height.min(bottom);
+        if height < 2 {
+            return;
+        }
+        let top = bottom - height;
+
+        self.canvas
+            .draw_rect_fill(&Rect::new(0, top, size.width(), height), &GRAPH_BACKGROUND);
+
+        let samples: Vec<Sample> = state
+            .history
+            .latest(size.width() as usize)
+            .copied()
+            .collect();
+        let maximum = samples.iter().map(|s| s.population).max().unwrap_or(0);
+        let to_y = |value: usize| {
+            bottom
+                - 1
+                - (scale(value, maximum, state.graph_logarithmic) * (height - 1) as f32) as i32
+        };
+
+        for (x, sample) in samples.iter().enumerate() {
+            let x = x as i32;
+            self.canvas.draw_line(
+                &Point::new(x, bottom - 1),
+                &Point::new(x, to_y(sample.population)),
+                &GRAPH_POPULATION,
+            );
+
+            if state.graph_births_and_deaths {
+                self.canvas
+                    .draw_pixel(&Point::new(x, to_y(sample.births)), &GRAPH_BIRTHS);
+                self.canvas
+                    .draw_pixel(&Point::new(x, to_y(sample.deaths)), &GRAPH_DEATHS);
+            }
+        }
+    }
+
+    pub fn draw_sparkline(&mut self, state: &State) {
+        if !state.sparkline_visible {
+            return;
+        }
+
+        let text_size = self.canvas.text_size();
+        let label = format!(
+            "gen {} pop {} ",
+            state.world.generation, state.world.statistics.population
+        );
+        let width = (text_size.width() as usize).saturating_sub(label.chars().count());
+
+        let chars = if self.canvas.is_unicode() {
+            &SPARKLINE_CHARS
+        } else {
+            &SPARKLINE_ASCII_CHARS
+        };
+        let populations: Vec<usize> = state.history.latest(width).map(|s| s.population).collect();
+        let text = format!(
+            "{label}{:<width$}",
+            sparkline(&populations, state.graph_logarithmic, chars)
+        );
+
+        let row = self.sparkline_row(state);
+        self.canvas.draw_text_at(0, row, &self.text_color, &text);
+    }
+
+    /// The sparkline moves up while the command line or a message takes the
+    /// last row, unless the status bar already made room for them.
+    fn sparkline_row(&self, state: &State) -> i32 {
+        let command_line_visible = state.command_line.is_some() || state.message.is_some();
+        self.bottom_text_row(state) - (command_line_visible && !state.status_bar_visible) as i32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_draws_a_linear_sparkline() {
+        assert_eq!(
+            sparkline(&[0, 1, 2, 3, 4, 5, 6, 7], false, &SPARKLINE_CHARS),
+            "▁▂▃▄▅▆▇█"
+        );
+        assert_eq!(sparkline(&[0, 0], false, &SPARKLINE_CHARS), "▁▁");
+        assert_eq!(sparkline(&[], false, &SPARKLINE_CHARS), "");
+    }
+
+    #[test]
+    fn it_draws_a_logarithmic_sparkline() {
+        assert_eq!(
+            sparkline(&[0, 10, 100, 1000], true, &SPARKLINE_CHARS),
+            "▁▃▆█"
+        );
+        assert_eq!(
+            sparkline(&[0, 10, 100, 1000], true, &SPARKLINE_ASCII_CHARS),
+            "_,=#"
+        );
+    }
+}
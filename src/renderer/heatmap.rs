@@ -0,0 +1,77 @@
+use crate::common::gradient::Gradient;
+use crate::state::State;
+use term2d::model::point::Point;
+use term2d::model::rgba::Rgba;
+use term2d::view::canvas::Canvas;
+
+use super::Renderer;
+
+const HEATMAP_COLORS: [(f32, Rgba); 5] = [
+    (
+        0.0,
+        Rgba {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 255,
+        },
+    ),
+    (
+        0.25,
+        Rgba {
+            r: 40,
+            g: 20,
+            b: 140,
+            a: 255,
+        },
+    ),
+    (
+        0.5,
+        Rgba {
+            r: 200,
+            g: 30,
+            b: 60,
+            a: 255,
+        },
+    ),
+    (
+        0.75,
+        Rgba {
+            r: 250,
+            g: 170,
+            b: 20,
+            a: 255,
+        },
+    ),
+    (
+        1.0,
+        Rgba {
+            r: 255,
+            g: 255,
+            b: 255,
+            a: 255,
+        },
+    ),
+];
+
+/// The colors of the heatmap from inactive to the most active cells.
+pub fn heatmap_gradient() -> Gradient {
+    Gradient::new(HEATMAP_COLORS.to_vec())
+}
+
+impl Renderer {
+    /// Draws the activity of every cell instead of its state, on a
+    /// logarithmic scale so that rarely touched cells remain visible.
+    pub fn draw_heatmap(&mut self, state: &State) {
+        let heatmap = &state.world.heatmap;
+
+        for y in 0..state.world.size.height() {
+            for x in 0..state.world.size.width() {
+                let p = Point::new(x, y);
+                let mut color = self.heatmap_gradient.at(heatmap.intensity(&p));
+                color.a = state.world.color_alpha;
+                self.canvas.draw_pixel(&p, &color);
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::state::State;
+use term2d::model::rgba::Rgba;
+use term2d::view::canvas::Canvas;
+
+use super::Renderer;
+
+const KEY_HELP_BACKGROUND: Rgba = Rgba {
+    r: 0,
+    g: 0,
+    b: 0,
+    a: 200,
+};
+
+impl Renderer {
+    /// Draws the current key bindings in the center of the screen, as many
+    /// as fit.
+    pub fn draw_key_help(&mut self, state: &State) {
+        if !state.key_help_visible {
+            return;
+        }
+
+        let text_size = self.canvas.text_size();
+        let mut lines = vec!["keys, action and description".to_string()];
+        lines.extend(state.args.keys.lines());
+        lines.truncate(text_size.height().max(0) as usize);
+
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as i32;
+        let column = ((text_size.width() - width) / 2).max(0);
+        let row = ((text_size.height() - lines.len() as i32) / 2).max(0);
+
+        self.canvas.draw_rect_fill(
+            &self
+                .canvas
+                .text_rect(column, row, width, lines.len() as i32),
+            &KEY_HELP_BACKGROUND,
+        );
+        for (i, line) in lines.iter().enumerate() {
+            self.canvas
+                .draw_text_at(column, row + i as i32, &self.text_color, line);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::env;
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use term2d::model::point::Point;
+
+use crate::state::State;
+
+/// Writes the frames of a session to an asciicast v2 file, see
+/// https://docs.asciinema.org/manual/asciicast/v2/
+pub struct Recorder<W: Write> {
+    last_generation: Option<u64>,
+    out: W,
+    simulation_only: bool,
+    size: Option<Point>,
+    start: Instant,
+    time: f64,
+}
+
+impl Recorder<BufWriter<File>> {
+    pub fn create(path: &Path, simulation_only: bool) -> io::Result<Self> {
+        Ok(Self::new(
+            BufWriter::new(File::create(path)?),
+            simulation_only,
+        ))
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    fn new(out: W, simulation_only: bool) -> Self {
+        Self {
+            last_generation: None,
+            out,
+            simulation_only,
+            size: None,
+            start: Instant::now(),
+            time: 0.0,
+        }
+    }
+
+    /// Records a frame of the given size in characters. Frames are stamped
+    /// with the time since the start, or with the frame rate if only frames
+    /// in which the world advanced are recorded.
+    pub fn record(&mut self, state: &State, size: &Point, frame: &str) -> io::Result<()> {
+        let generation = state.world.generation;
+        if self.simulation_only {
+            if self.last_generation == Some(generation) {
+                return Ok(());
+            }
+            if self.last_generation.is_some() {
+                self.time += 1.0 / state.args.frames_per_second as f64;
+            }
+        } else {
+            self.time = self.start.elapsed().as_secs_f64();
+        }
+        self.last_generation = Some(generation);
+
+        match &self.size {
+            None => self.write_header(size)?,
+            Some(last_size) if last_size != size => writeln!(
+                self.out,
+                "[{:.6}, \"r\", \"{}x{}\"]",
+                self.time,
+                size.width(),
+                size.height()
+            )?,
+            Some(_) => {}
+        }
+        self.size = Some(size.clone());
+
+        writeln!(
+            self.out,
+            "[{:.6}, \"o\", {}]",
+            self.time,
+            json_string(frame)
+        )?;
+        self.out.flush()
+    }
+
+    fn write_header(&mut self, size: &Point) -> io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        let term = env::var("TERM").unwrap_or_else(|_| "xterm-256color".to_string());
+
+        writeln!(
+            self.out,
+            "{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {timestamp}, \
+             \"title\": \"termgol\", \"env\": {{\"TERM\": {}}}}}",
+            size.width(),
+            size.height(),
+            json_string(&term)
+        )
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::args::Args;
+
+    #[test]
+    fn it_records_asciicast_v2() {
+        let mut state = State::from(Args::default());
+        state.resize(&Point::new(20, 10));
+        let mut recorder = Recorder::new(Vec::new(), true);
+
+        recorder
+            .record(&state, &Point::new(20, 5), "\x1b[0m\"a\"")
+            .unwrap();
+        recorder.record(&state, &Point::new(20, 5), "edit").unwrap();
+        state.world.update();
+        recorder.record(&state, &Point::new(30, 5), "b").unwrap();
+
+        let cast = String::from_utf8(recorder.out).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+        assert!(lines[0].starts_with("{\"version\": 2, \"width\": 20, \"height\": 5,"));
+        assert_eq!(lines[1], "[0.000000, \"o\", \"\\u001b[0m\\\"a\\\"\"]");
+        assert_eq!(lines[2], "[0.100000, \"r\", \"30x5\"]");
+        assert_eq!(lines[3], "[0.100000, \"o\", \"b\"]");
+        assert_eq!(lines.len(), 4);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::state::State;
+use term2d::model::rgba::Rgba;
+use term2d::view::canvas::Canvas;
+
+use super::Renderer;
+
+const SELECTION_COLOR: Rgba = Rgba {
+    r: 255,
+    g: 255,
+    b: 255,
+    a: 60,
+};
+
+impl Renderer {
+    pub fn draw_selection(&mut self, state: &State) {
+        if let Some(selection) = state.selection() {
+            self.canvas.draw_rect_fill(&selection, &SELECTION_COLOR);
+        }
+    }
+}
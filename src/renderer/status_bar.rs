@@ -0,0 +1,67 @@
+use crate::state::State;
+
+use super::Renderer;
+
+impl Renderer {
+    /// Draws the status bar in the last text row, below the world.
+    pub fn draw_status_bar(&mut self, state: &State) {
+        if !state.status_bar_visible {
+            return;
+        }
+
+        let text_size = self.canvas.text_size();
+        let width = text_size.width().max(0) as usize;
+        let text = format!("{:<width$}", status_text(state));
+
+        self.canvas
+            .draw_text_at(0, text_size.height() - 1, &self.text_color, &text);
+    }
+}
+
+fn status_text(state: &State) -> String {
+    let world = &state.world;
+    let mut parts = vec![
+        format!("gen {}", world.generation),
+        format!("pop {}", world.statistics.population),
+        world.rules.to_string(),
+        format!("{} fps", state.args.frames_per_second),
+        state.mode().to_string(),
+        format!("torus {}x{}", world.size.width(), world.size.height()),
+    ];
+    if state.pause {
+        parts.push(format!(
+            "cursor {},{}",
+            state.cursor_pos.x, state.cursor_pos.y
+        ));
+    }
+
+    parts.join(" | ")
+}
+
+#[cfg(test)]
+mod test {
+    use term2d::model::point::Point;
+
+    use super::*;
+    use crate::common::args::Args;
+
+    #[test]
+    fn it_shows_the_cursor_when_paused() {
+        let mut state = State::from(Args {
+            paused: true,
+            ..Args::default()
+        });
+        state.resize(&Point::new(20, 10));
+
+        assert_eq!(
+            status_text(&state),
+            "gen 0 | pop 5 | B3/S23 | 10 fps | paused | torus 20x10 | cursor 0,0"
+        );
+
+        state.toggle_pause();
+        assert_eq!(
+            status_text(&state),
+            "gen 0 | pop 5 | B3/S23 | 10 fps | running | torus 20x10"
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use std::cmp::min;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+use nanorand::Rng;
+use nanorand::WyRand;
+
+use crate::common::args::SearchArgs;
+use crate::state::apgcode;
+use crate::state::cell_image::CellImage;
+use crate::state::cell_setup::CellSetup;
+use crate::state::census::Census;
+use crate::state::census::UNIDENTIFIED;
+use crate::state::rules::Rules;
+use crate::state::universe::Universe;
+
+const CENSUS_FILE: &str = "census.txt";
+const PROGRESS_FILE: &str = "progress.txt";
+const RARE_DIRECTORY: &str = "rare";
+
+const SEARCH_BATCH_SIZE: u64 = 1024;
+const SETTLE_MAX_PERIOD: usize = 60;
+const SETTLE_WINDOW: usize = 4 * SETTLE_MAX_PERIOD;
+const SOUP_SIZE: i32 = 16;
+
+/// The apgcodes of objects which are too common to be reported as rare.
+const COMMON_OBJECTS: [&str; 14] = [
+    "xs4_33", "xp2_7", "xs6_696", "xq4_153", "xs7_2596", "xs5_253", "xs6_356", "xs4_252",
+    "xs8_6996", "xs7_25ac", "xp2_7e", "xs6_25a4", "xp2_318c", "xs8_69ic",
+];
+
+/// Whether an object is worth saving, objects which did not settle into
+/// something periodic are not identified and never rare.
+fn is_rare(apgcode: &str) -> bool {
+    apgcode != UNIDENTIFIED && !COMMON_OBJECTS.contains(&apgcode)
+}
+
+/// Identifies a search, so that it can be continued after an interruption.
+#[derive(Clone, Debug, PartialEq)]
+struct Progress {
+    rules: Rules,
+    seed: u64,
+    soups: u64,
+}
+
+impl Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "rules: {}", self.rules)?;
+        writeln!(f, "seed: {}", self.seed)?;
+        writeln!(f, "soups: {}", self.soups)
+    }
+}
+
+impl Progress {
+    fn parse(s: &str) -> Option<Self> {
+        let values: HashMap<&str, &str> = s
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .collect();
+
+        Some(Self {
+            rules: Rules::parse(values.get("rules")?).ok()?,
+            seed: values.get("seed")?.parse().ok()?,
+            soups: values.get("soups")?.parse().ok()?,
+        })
+    }
+}
+
+/// Runs seeded soups until interrupted or the requested number of soups is
+/// reached, the census totals and the progress are saved after every batch.
+pub fn run(args: SearchArgs) -> io::Result<()> {
+    if args.rules.birth.contains(&0) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "cannot search with rules {}, births without neighbours are not supported",
+                args.rules
+            ),
+        ));
+    }
+
+    fs::create_dir_all(args.results.join(RARE_DIRECTORY))?;
+    let mut progress = resume(&args)?;
+    let mut totals = load_census(&args.results.join(CENSUS_FILE))?;
+    let threads = args
+        .threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+        .max(1);
+
+    eprintln!(
+        "searching with rules {} from seed {} on {threads} threads, {} soups done",
+        progress.rules, progress.seed, progress.soups
+    );
+
+    let start = Instant::now();
+    let start_soups = progress.soups;
+
+    loop {
+        let batch_end = progress.soups + SEARCH_BATCH_SIZE;
+        let batch_end = args.soups.map_or(batch_end, |soups| min(soups, batch_end));
+        if batch_end <= progress.soups {
+            return Ok(());
+        }
+
+        for (seed, census) in run_batch(&progress, batch_end, threads, args.generations) {
+            for entry in &census.entries {
+                *totals.entry(entry.apgcode.clone()).or_insert(0) += entry.count as u64;
+
+                if is_rare(&entry.apgcode) {
+                    save_rare(&args.results, &progress.rules, seed, &entry.apgcode)?;
+                    println!("{} in soup {seed}", entry.apgcode);
+                }
+            }
+        }
+
+        progress.soups = batch_end;
+        save_census(&args.results.join(CENSUS_FILE), &totals)?;
+        fs::write(args.results.join(PROGRESS_FILE), progress.to_string())?;
+
+        eprintln!(
+            "{} soups, {:.0} soups/s",
+            progress.soups,
+            (progress.soups - start_soups) as f64 / start.elapsed().as_secs_f64()
+        );
+    }
+}
+
+/// Loads the progress of an earlier search in the results directory or
+/// starts a new one.
+fn resume(args: &SearchArgs) -> io::Result<Progress> {
+    let path = args.results.join(PROGRESS_FILE);
+    if !path.exists() {
+        return Ok(Progress {
+            rules: args.rules.clone(),
+            seed: args.seed.unwrap_or_else(|| WyRand::new().generate()),
+            soups: 0,
+        });
+    }
+
+    let progress = Progress::parse(&fs::read_to_string(&path)?).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid progress file {}", path.display()),
+        )
+    })?;
+
+    if progress.rules != args.rules || args.seed.is_some_and(|seed| seed != progress.seed) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} belongs to a search with rules {} and seed {}",
+                args.results.display(),
+                progress.rules,
+                progress.seed
+            ),
+        ));
+    }
+
+    Ok(progress)
+}
+
+/// Runs the soups up to `batch_end` on the threads and returns their seeds
+/// and censuses.
+fn run_batch(
+    progress: &Progress,
+    batch_end: u64,
+    threads: usize,
+    max_generations: u64,
+) -> Vec<(u64, Census)> {
+    let next_soup = AtomicU64::new(progress.soups);
+    let results = Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let soup = next_soup.fetch_add(1, Ordering::Relaxed);
+                if soup >= batch_end {
+                    return;
+                }
+
+                let seed = progress.seed.wrapping_add(soup);
+                let census = run_soup(seed, &progress.rules, max_generations);
+                results.lock().unwrap().push((seed, census));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(seed, _)| *seed);
+    results
+}
+
+fn soup(seed: u64) -> CellImage {
+    CellImage::from_cell_setup(&CellSetup::RectSoup(SOUP_SIZE, SOUP_SIZE), seed)
+}
+
+/// Computes the soup until it settles and takes the census of its ash.
+fn run_soup(seed: u64, rules: &Rules, max_generations: u64) -> Census {
+    let mut universe = Universe::new(&soup(seed), rules.clone());
+    let mut populations = Vec::new();
+
+    while universe.generation < max_generations {
+        populations.push(universe.living_points.len());
+        if populations.len() % SETTLE_MAX_PERIOD == 0 && is_settled(&populations) {
+            break;
+        }
+        universe.update();
+    }
+
+    Census::take(&universe.cell_image(), None, rules, universe.generation)
+}
+
+/// A soup is considered settled when its population is periodic over the
+/// last generations, escaping spaceships do not change the population.
+fn is_settled(populations: &[usize]) -> bool {
+    if populations.len() < SETTLE_WINDOW {
+        return false;
+    }
+
+    let window = &populations[populations.len() - SETTLE_WINDOW..];
+    (1..=SETTLE_MAX_PERIOD)
+        .any(|period| window.iter().skip(period).zip(window).all(|(a, b)| a == b))
+}
+
+fn save_rare(results: &Path, rules: &Rules, seed: u64, apgcode: &str) -> io::Result<()> {
+    let comments = [
+        format!("Seed: {seed}"),
+        format!("Object: {apgcode}"),
+        format!("Reproduce with: termgol -c soup{SOUP_SIZE:03} --seed {seed} -r {rules}"),
+    ];
+    let path = results
+        .join(RARE_DIRECTORY)
+        .join(format!("{apgcode}_{seed}.rle"));
+
+    fs::write(path, soup(seed).to_rle(rules, &comments))
+}
+
+/// Reads census totals, one object per line starting with its count and
+/// apgcode.
+fn load_census(path: &Path) -> io::Result<HashMap<String, u64>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let count = words.next()?.parse().ok()?;
+            Some((words.next()?.to_string(), count))
+        })
+        .collect())
+}
+
+fn save_census(path: &Path, totals: &HashMap<String, u64>) -> io::Result<()> {
+    let mut entries: Vec<(&String, &u64)> = totals.iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    let lines: String = entries
+        .into_iter()
+        .map(|(apgcode, count)| match apgcode::object_name(apgcode) {
+            Some(name) => format!("{count} {apgcode} {name}\n"),
+            None => format!("{count} {apgcode}\n"),
+        })
+        .collect();
+
+    fs::write(path, lines)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_detects_settled_populations() {
+        assert!(!is_settled(&[5; SETTLE_WINDOW - 1]));
+        assert!(is_settled(&[5; SETTLE_WINDOW]));
+
+        let oscillating: Vec<usize> = (0..SETTLE_WINDOW).map(|i| 10 + i % 7).collect();
+        assert!(is_settled(&oscillating));
+
+        let growing: Vec<usize> = (0..SETTLE_WINDOW).collect();
+        assert!(!is_settled(&growing));
+    }
+
+    #[test]
+    fn it_reports_only_uncommon_identified_objects_as_rare() {
+        assert!(!is_rare("xs4_33"));
+        assert!(!is_rare("xs8_69ic"));
+        assert!(!is_rare(UNIDENTIFIED));
+        assert!(is_rare("xs6_39c"));
+        assert!(is_rare("xp3_co9nas0san9oczgoldlo0oldlogz1047210127401"));
+    }
+
+    #[test]
+    fn it_names_all_common_objects() {
+        for apgcode in COMMON_OBJECTS {
+            assert!(apgcode::object_name(apgcode).is_some(), "{apgcode}");
+        }
+    }
+
+    #[test]
+    fn it_refuses_b0_rules() {
+        let results =
+            std::env::temp_dir().join(format!("termgol-test-search-{}", std::process::id()));
+        let args = SearchArgs {
+            generations: 100,
+            results: results.clone(),
+            rules: Rules::parse("B03/S23").unwrap(),
+            seed: None,
+            soups: Some(1),
+            threads: Some(1),
+        };
+        assert!(run(args).is_err());
+        assert!(!results.exists());
+    }
+
+    #[test]
+    fn it_parses_its_own_progress() {
+        let progress = Progress {
+            rules: Rules::parse("B36/S23").unwrap(),
+            seed: 42,
+            soups: 2048,
+        };
+        assert_eq!(Progress::parse(&progress.to_string()), Some(progress));
+        assert_eq!(Progress::parse("seed: 42"), None);
+    }
+
+    #[test]
+    fn it_runs_reproducible_soups() {
+        let rules = Rules::parse("B3/S23").unwrap();
+        let census = run_soup(7, &rules, 4000);
+        assert!(census.object_count() > 0);
+        assert_eq!(census, run_soup(7, &rules, 4000));
+    }
+}
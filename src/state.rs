@@ -1,65 +1,450 @@
 use std::cmp::max;
 use std::cmp::min;
+use std::collections::VecDeque;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
 use crate::common::args::Args;
-use crate::common::DEBUG_INFO_PAGE_TOTAL;
+use crate::common::color::RgbaParser;
+use crate::common::key_bindings::key_name;
+use crate::common::rainbow::Rainbow;
+use crate::common::TICKS_PER_SECOND;
+use crate::export;
+use term2d::model::key::Key;
 use term2d::model::point::Point;
-
+use term2d::model::rect::Rect;
+use term2d::model::rgba::Rgba;
+
+use self::cell_image::CellImage;
+use self::census::Census;
+use self::command::Command;
+use self::history::History;
+use self::history::Sample;
+use self::history::HISTORY_CAPACITY;
+use self::identification::Identification;
+use self::identification::IDENTIFICATION_MAX_GENERATIONS;
+use self::period_detector::PeriodDetector;
+use self::period_detector::PERIOD_DETECTOR_CAPACITY;
+use self::rules::Rules;
+use self::rules::RULES_PRESETS;
+use self::timing::Timing;
 use self::world::World;
 
+pub mod apgcode;
 mod cell;
-mod world;
 
 pub mod cell_image;
 pub mod cell_setup;
+pub mod census;
+pub mod command;
+pub mod heatmap;
+pub mod history;
+pub mod identification;
+pub mod period_detector;
 pub mod rules;
+pub mod statistics;
+pub mod timing;
+pub mod universe;
+pub mod world;
+
+const ALIVE_COLORS: [&str; 6] = [
+    "#EE8822", "#22EE88", "#2288EE", "#EE2288", "#EEDD22", "#EEEEEE",
+];
+
+/// How long a frame may compute generations towards a goto target, so that
+/// the ui stays responsive during long jumps.
+const GOTO_FRAME_BUDGET: Duration = Duration::from_millis(50);
+
+/// The number of key presses shown in the debug info.
+const INPUT_EVENTS_CAPACITY: usize = 8;
 
 pub struct State {
+    pub actual_frames_per_second: f32,
     pub args: Args,
+    pub census: Option<Census>,
+    pub command_line: Option<String>,
     pub cursor_pos: Point,
-    pub debug_info_page: i32,
+    pub debug_info_page: usize,
+    pub drawing: bool,
     pub elapsed_time: u64,
+    pub fast_forward: bool,
+    pub goto_generation: Option<u64>,
+    pub graph_births_and_deaths: bool,
+    pub graph_height: i32,
+    pub graph_logarithmic: bool,
+    pub graph_visible: bool,
+    pub heatmap_visible: bool,
+    pub history: History,
+    pub identification: Option<Identification>,
+    pub input_events: VecDeque<String>,
+    pub key_help_visible: bool,
+    pub last_frame: Option<Instant>,
+    pub message: Option<String>,
+    pub next_frame: Instant,
     pub pause: bool,
+    pending_census: Option<Receiver<Census>>,
+    pending_identification: Option<Receiver<Option<Identification>>>,
+    pub period_detector: PeriodDetector,
     pub screen_size: Point,
+    pub selection_anchor: Option<Point>,
+    pub sparkline_visible: bool,
+    pub status_bar_visible: bool,
+    pub timing: Timing,
     pub world: World,
 }
 
 impl From<Args> for State {
-    fn from(args: Args) -> Self {
+    fn from(mut args: Args) -> Self {
+        args.resolve_seed();
         let pause = args.paused;
+        let status_bar_visible = args.status_bar;
         let args_clone = args.clone();
 
         Self {
+            actual_frames_per_second: 0.0,
             args,
+            census: None,
+            command_line: None,
             cursor_pos: Point::new(0, 0),
             debug_info_page: 0,
+            drawing: false,
             elapsed_time: 0,
+            fast_forward: false,
+            goto_generation: None,
+            graph_births_and_deaths: false,
+            graph_height: 16,
+            graph_logarithmic: false,
+            graph_visible: false,
+            heatmap_visible: false,
+            history: History::new(HISTORY_CAPACITY),
+            identification: None,
+            input_events: VecDeque::with_capacity(INPUT_EVENTS_CAPACITY),
+            key_help_visible: false,
+            last_frame: None,
+            message: None,
+            next_frame: Instant::now(),
             pause,
+            pending_census: None,
+            pending_identification: None,
+            period_detector: PeriodDetector::new(PERIOD_DETECTOR_CAPACITY),
             screen_size: Point::new(0, 0),
+            selection_anchor: None,
+            sparkline_visible: false,
+            status_bar_visible,
+            timing: Timing::default(),
             world: World::from(args_clone),
         }
     }
 }
 
 impl State {
+    /// Identifies a fixed cell setup in the background, since a large
+    /// pattern takes a while, the result is shown once it is done.
+    pub fn identify_in_background(&mut self) {
+        if self.args.cell_setup.is_random() || self.args.screen_saver.is_some() {
+            return;
+        }
+
+        let cell_image = CellImage::from_cell_setup(&self.args.cell_setup, self.world.seed);
+        let rules = self.args.rules.clone();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(Identification::identify(
+                &cell_image,
+                &rules,
+                IDENTIFICATION_MAX_GENERATIONS,
+            ));
+        });
+        self.pending_identification = Some(receiver);
+    }
+
+    /// Sets up the world again in the new size of the screen, without the
+    /// status bar.
     pub fn resize(&mut self, screen_size: &Point) {
-        self.screen_size = screen_size.clone();
-        self.world.resize(screen_size);
+        self.resize_world(screen_size);
+        self.world.setup_cells();
+        self.reset_history();
+
+        if let Some(generation) = self.args.until.take() {
+            self.goto(generation);
+        }
     }
 
-    pub fn elapse_time(&mut self) {
-        if self.pause {
-            return;
+    /// Changes the size of the world and keeps its cells, e.g. when the status
+    /// bar is shown or hidden.
+    pub fn resize_world(&mut self, size: &Point) {
+        self.screen_size = size.clone();
+        self.world.resize(size);
+        self.cursor_pos = Point::new(
+            self.cursor_pos.x.clamp(0, max(0, size.width() - 1)),
+            self.cursor_pos.y.clamp(0, max(0, size.height() - 1)),
+        );
+    }
+
+    /// Advances the world if the next frame is due, returns whether it did.
+    pub fn elapse_time(&mut self) -> bool {
+        let received = self.receive_identification() | self.receive_census();
+
+        if self.goto_generation.is_some() {
+            self.advance_to_goto_generation();
+            return true;
         }
 
+        let now = Instant::now();
+        if self.pause || now < self.next_frame {
+            return received;
+        }
+
+        let frame_duration = Duration::from_millis(1000 / self.args.frames_per_second as u64);
+        self.next_frame = max(self.next_frame + frame_duration, now);
+        self.measure_frames_per_second(now, frame_duration);
+
         self.elapsed_time += 1;
 
         if self.elapsed_time * 1000 / (self.args.frames_per_second as u64) < self.args.delay {
-            return;
+            return true;
         }
 
         self.handle_screen_saver();
+        self.world.drift_rainbow();
+
+        let generations = if self.fast_forward {
+            max(1, self.args.fast_forward)
+        } else {
+            1
+        };
+        let start = Instant::now();
+        for _ in 0..generations {
+            self.update_world();
+        }
+        self.timing.record_update(start.elapsed());
+
+        true
+    }
+
+    /// Shows the identification of the cell setup once the background
+    /// computation is done, returns whether it was received.
+    fn receive_identification(&mut self) -> bool {
+        let Some(receiver) = &self.pending_identification else {
+            return false;
+        };
+        let Ok(identification) = receiver.try_recv() else {
+            return false;
+        };
+
+        self.pending_identification = None;
+        if let Some(identification) = &identification {
+            self.message = Some(format!("cell setup: {identification}"));
+        }
+        self.identification = identification;
+        true
+    }
+
+    fn measure_frames_per_second(&mut self, now: Instant, frame_duration: Duration) {
+        if let Some(last_frame) = self.last_frame.replace(now) {
+            let interval = now.duration_since(last_frame);
+            self.timing.record_jitter(interval, frame_duration);
+            let fps = 1.0 / interval.as_secs_f32();
+            self.actual_frames_per_second = 0.9 * self.actual_frames_per_second + 0.1 * fps;
+        }
+    }
+
+    fn update_world(&mut self) {
         self.world.update();
+        self.history.push(Sample::from(&self.world.statistics));
+        self.period_detector
+            .push(self.world.generation, self.world.statistics.hash);
+    }
+
+    fn reset_history(&mut self) {
+        self.history.clear();
+        self.history.push(Sample::from(&self.world.statistics));
+        self.restart_period_detection();
+    }
+
+    /// Forgets the earlier generations after edits and rule changes, which
+    /// make them useless for detecting a period.
+    fn restart_period_detection(&mut self) {
+        self.period_detector.clear();
+        self.period_detector
+            .push(self.world.generation, self.world.statistics.hash);
+    }
+
+    pub fn toggle_graph(&mut self) {
+        self.graph_visible = !self.graph_visible;
+    }
+
+    pub fn toggle_graph_births_and_deaths(&mut self) {
+        self.graph_births_and_deaths = !self.graph_births_and_deaths;
+    }
+
+    pub fn toggle_graph_logarithmic(&mut self) {
+        self.graph_logarithmic = !self.graph_logarithmic;
+        self.message = Some(format!(
+            "graph scale: {}",
+            if self.graph_logarithmic {
+                "logarithmic"
+            } else {
+                "linear"
+            }
+        ));
+    }
+
+    pub fn increase_graph_height(&mut self) {
+        self.graph_height = min(self.screen_size.height(), self.graph_height + 2);
+    }
+
+    pub fn decrease_graph_height(&mut self) {
+        self.graph_height = max(4, self.graph_height - 2);
+    }
+
+    pub fn toggle_sparkline(&mut self) {
+        self.sparkline_visible = !self.sparkline_visible;
+    }
+
+    pub fn toggle_status_bar(&mut self) {
+        self.status_bar_visible = !self.status_bar_visible;
+    }
+
+    /// The name of the current mode for the status bar.
+    pub fn mode(&self) -> &'static str {
+        match (self.pause, self.drawing, self.fast_forward) {
+            (true, true, _) => "drawing",
+            (true, false, _) => "paused",
+            (false, _, true) => "fast forward",
+            (false, _, false) => "running",
+        }
+    }
+
+    pub fn toggle_key_help(&mut self) {
+        self.key_help_visible = !self.key_help_visible;
+    }
+
+    /// Takes a census of the objects in the current generation in the
+    /// background, or hides it if it is shown or still being counted.
+    pub fn toggle_census(&mut self) {
+        if self.census.take().is_some() || self.pending_census.take().is_some() {
+            return;
+        }
+
+        let cell_image = self.world.cell_image();
+        let torus = self.world.size.clone();
+        let rules = self.world.rules.clone();
+        let generation = self.world.generation;
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(Census::take(&cell_image, Some(&torus), &rules, generation));
+        });
+
+        self.message = Some(format!("census: counting generation {generation}…"));
+        self.pending_census = Some(receiver);
+    }
+
+    /// Shows the census once the background computation is done, returns
+    /// whether it was received.
+    fn receive_census(&mut self) -> bool {
+        let Some(Ok(census)) = self.pending_census.as_ref().map(Receiver::try_recv) else {
+            return false;
+        };
+
+        self.pending_census = None;
+        self.message = Some(format!("census: {} objects", census.object_count()));
+        self.census = Some(census);
+        true
+    }
+
+    /// Saves the current frame as PNG, the message shows where.
+    pub fn snapshot(&mut self) {
+        self.message = Some(match export::snapshot(&self.world) {
+            Ok(path) => format!("saved {path}"),
+            Err(error) => format!("cannot save snapshot: {error}"),
+        });
+    }
+
+    /// Saves the selection or the whole world as SVG in pause mode, the
+    /// message shows where.
+    pub fn snapshot_svg(&mut self) {
+        if !self.pause {
+            return;
+        }
+
+        let area = self
+            .selection()
+            .unwrap_or_else(|| Rect::new(0, 0, self.world.size.width(), self.world.size.height()));
+        self.message = Some(match export::snapshot_svg(&self.world, &area, &self.args) {
+            Ok(path) => format!("saved {path}"),
+            Err(error) => format!("cannot save svg: {error}"),
+        });
+    }
+
+    pub fn step(&mut self) {
+        if !self.pause {
+            return;
+        }
+
+        self.update_world();
+        self.message = Some(format!("generation: {}", self.world.generation));
+    }
+
+    pub fn toggle_fast_forward(&mut self) {
+        self.fast_forward = !self.fast_forward;
+        self.message = Some(if self.fast_forward {
+            format!(
+                "fast forward: {} generations per frame",
+                self.args.fast_forward
+            )
+        } else {
+            "fast forward: off".to_string()
+        });
+    }
+
+    /// Computes the world up to the specified generation without showing the
+    /// generations in between and pauses there. Earlier generations cannot be
+    /// reached since edits and rule changes are not recorded.
+    pub fn goto(&mut self, generation: u64) {
+        if generation < self.world.generation {
+            self.message = Some(format!(
+                "cannot go back to generation {generation} from {}",
+                self.world.generation
+            ));
+            return;
+        }
+
+        self.goto_generation = Some(generation);
+        self.advance_to_goto_generation();
+    }
+
+    /// Computes generations towards the goto target within the frame budget.
+    fn advance_to_goto_generation(&mut self) {
+        let Some(generation) = self.goto_generation else {
+            return;
+        };
+
+        let start = Instant::now();
+        while self.world.generation < generation && start.elapsed() < GOTO_FRAME_BUDGET {
+            self.update_world();
+        }
+
+        if self.world.generation < generation {
+            self.message = Some(format!(
+                "going to generation {generation}: {}, pause to stop",
+                self.world.generation
+            ));
+        } else {
+            self.goto_generation = None;
+            self.pause = true;
+            self.message = Some(format!("generation: {}", self.world.generation));
+        }
+    }
+
+    fn reset_world(&mut self) {
+        self.world = World::from(self.args.clone());
+        self.world.resize(&self.screen_size);
+        self.world.setup_cells();
+        self.reset_history();
     }
 
     fn handle_screen_saver(&mut self) {
@@ -68,20 +453,207 @@ impl State {
                 self.world.color_alpha = min(255, self.world.color_alpha as i32 + 10) as u8;
             }
 
+            if self.args.reset_when_stable
+                && self.period_detector.period.is_some()
+                && self.elapsed_time < (screen_saver + 30) as u64
+            {
+                self.elapsed_time = (screen_saver + 30) as u64;
+            }
+
             if self.elapsed_time > (screen_saver + 30) as u64 {
                 self.world.color_alpha = max(0, self.world.color_alpha as i32 - 10) as u8;
             }
 
             if self.elapsed_time >= (screen_saver + 60) as u64 {
-                self.world = World::from(self.args.clone());
-                self.world.resize(&self.screen_size);
+                self.args.seed = Some(self.world.seed.wrapping_add(1));
+                self.reset_world();
                 self.elapsed_time = 0;
             }
         }
     }
 
+    pub fn open_command_line(&mut self) {
+        self.command_line = Some(String::new());
+    }
+
+    pub fn close_command_line(&mut self) {
+        self.command_line = None;
+    }
+
+    pub fn push_command_line(&mut self, c: char) {
+        if let Some(command_line) = &mut self.command_line {
+            command_line.push(c);
+        }
+    }
+
+    pub fn pop_command_line(&mut self) {
+        if let Some(command_line) = &mut self.command_line {
+            command_line.pop();
+        }
+    }
+
+    pub fn submit_command_line(&mut self) {
+        if let Some(command_line) = self.command_line.take() {
+            match Command::parse(&command_line) {
+                Ok(command) => self.execute_command(command),
+                Err(error) => self.message = Some(error),
+            }
+        }
+    }
+
+    pub fn clear_message(&mut self) {
+        self.message = None;
+    }
+
+    pub fn execute_command(&mut self, command: Command) {
+        match command {
+            Command::Age(enabled) => {
+                self.set_color_by_age(enabled.unwrap_or(!self.args.color_by_age));
+            }
+            Command::Alive(color) => self.set_color_bg_alive(color),
+            Command::Dead(color) => self.set_color_bg_dead(color),
+            Command::Fading(fading_speed) => self.set_fading_speed(fading_speed),
+            Command::Fps(fps) => self.set_frames_per_second(fps),
+            Command::Goto(generation) => self.goto(generation),
+            Command::Heatmap(visible) => {
+                self.set_heatmap_visible(visible.unwrap_or(!self.heatmap_visible));
+            }
+            Command::Rainbow(enabled) => {
+                self.set_rainbow(enabled.unwrap_or(!self.args.rainbow));
+            }
+            Command::ResetHeatmap => self.reset_heatmap(),
+            Command::Rules(rules) => self.set_rules(rules),
+        }
+    }
+
+    pub fn next_rules(&mut self) {
+        let i = self.args.rules.preset_index().map_or(0, |i| i + 1);
+        self.set_rules(Rules::preset(i));
+    }
+
+    pub fn previous_rules(&mut self) {
+        let i = self.args.rules.preset_index().unwrap_or(0);
+        self.set_rules(Rules::preset(i + RULES_PRESETS.len() - 1));
+    }
+
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.message = Some(match rules.name() {
+            Some(name) => format!("rules: {rules} ({name})"),
+            None => format!("rules: {rules}"),
+        });
+        self.args.rules = rules.clone();
+        self.world.rules = rules;
+        self.restart_period_detection();
+    }
+
+    pub fn increase_frames_per_second(&mut self) {
+        let fps = self.args.frames_per_second;
+        self.set_frames_per_second(fps.saturating_add(max(1, fps / 10)));
+    }
+
+    pub fn decrease_frames_per_second(&mut self) {
+        let fps = self.args.frames_per_second;
+        self.set_frames_per_second(fps.saturating_sub(max(1, fps / 11)));
+    }
+
+    /// Sets the frames per second, frames are scheduled on the ticks of the
+    /// terminal so there are at most as many.
+    pub fn set_frames_per_second(&mut self, fps: u16) {
+        let fps = fps.clamp(1, TICKS_PER_SECOND);
+        self.message = Some(format!("fps: {fps}"));
+        self.args.frames_per_second = fps;
+        self.next_frame = Instant::now();
+    }
+
+    pub fn increase_fading_speed(&mut self) {
+        self.set_fading_speed(self.args.fading_speed + 10);
+    }
+
+    pub fn decrease_fading_speed(&mut self) {
+        self.set_fading_speed(self.args.fading_speed - 10);
+    }
+
+    pub fn set_fading_speed(&mut self, fading_speed: i32) {
+        let fading_speed = fading_speed.clamp(-255, 255);
+        self.message = Some(format!("fading speed: {fading_speed}"));
+        self.args.fading_speed = fading_speed;
+        self.world.fading_speed = fading_speed;
+    }
+
+    pub fn next_color_bg_alive(&mut self) {
+        let colors: Vec<Rgba> = ALIVE_COLORS
+            .iter()
+            .map(|c| RgbaParser::parse(c).unwrap())
+            .collect();
+        let i = colors
+            .iter()
+            .position(|c| c == &self.args.color_bg_alive)
+            .map_or(0, |i| (i + 1) % colors.len());
+        self.set_color_bg_alive(colors[i].clone());
+    }
+
+    pub fn set_color_bg_alive(&mut self, color: Rgba) {
+        self.message = Some(format!("alive color: {}", RgbaParser::format(&color)));
+        self.args.color_bg_alive = color.clone();
+        self.world.color_bg_alive = color;
+        self.world.recolor();
+    }
+
+    pub fn set_color_bg_dead(&mut self, color: Rgba) {
+        self.message = Some(format!("dead color: {}", RgbaParser::format(&color)));
+        self.args.color_bg_dead = color.clone();
+        self.world.color_bg_dead = color;
+    }
+
+    pub fn toggle_rainbow(&mut self) {
+        self.set_rainbow(!self.args.rainbow);
+    }
+
+    pub fn set_rainbow(&mut self, rainbow: bool) {
+        self.message = Some(format!("rainbow: {}", if rainbow { "on" } else { "off" }));
+        self.args.rainbow = rainbow;
+        self.world
+            .set_rainbow(rainbow.then(|| Rainbow::from(&self.args)));
+    }
+
+    pub fn toggle_color_by_age(&mut self) {
+        self.set_color_by_age(!self.args.color_by_age);
+    }
+
+    pub fn set_color_by_age(&mut self, color_by_age: bool) {
+        self.message = Some(format!(
+            "color by age: {}",
+            if color_by_age { "on" } else { "off" }
+        ));
+        self.args.color_by_age = color_by_age;
+        self.world.color_by_age = color_by_age;
+        self.world.recolor();
+    }
+
+    pub fn toggle_heatmap(&mut self) {
+        self.set_heatmap_visible(!self.heatmap_visible);
+    }
+
+    pub fn set_heatmap_visible(&mut self, visible: bool) {
+        self.message = Some(format!("heatmap: {}", if visible { "on" } else { "off" }));
+        self.heatmap_visible = visible;
+    }
+
+    pub fn reset_heatmap(&mut self) {
+        self.message = Some("heatmap reset".to_string());
+        self.world.heatmap.clear();
+    }
+
     pub fn toggle_pause(&mut self) {
+        if self.goto_generation.take().is_some() {
+            self.pause = true;
+            self.message = Some(format!("generation: {}", self.world.generation));
+            return;
+        }
+
         self.pause = !self.pause;
+        self.drawing = false;
+        self.last_frame = None;
     }
 
     pub fn toggle_life_at_cursor(&mut self) {
@@ -90,19 +662,69 @@ impl State {
         }
 
         let i = (self.world.size.width() * self.cursor_pos.y + self.cursor_pos.x) as usize;
-        if self.world.cells[i].alive {
-            self.world.set_dead(i);
-        } else {
-            self.world.set_alive(i);
+        self.world.toggle_life(i);
+        self.drawing = true;
+        self.restart_period_detection();
+    }
+
+    pub fn toggle_selection(&mut self) {
+        if !self.pause {
+            return;
         }
+
+        self.selection_anchor = match self.selection_anchor {
+            Some(_) => None,
+            None => Some(self.cursor_pos.clone()),
+        };
+    }
+
+    /// The rectangle spanned by the selection anchor and the cursor.
+    pub fn selection(&self) -> Option<Rect> {
+        let anchor = self.selection_anchor.as_ref()?;
+        let x0 = min(anchor.x, self.cursor_pos.x);
+        let y0 = min(anchor.y, self.cursor_pos.y);
+        let x1 = max(anchor.x, self.cursor_pos.x);
+        let y1 = max(anchor.y, self.cursor_pos.y);
+        Some(Rect::new(x0, y0, x1 - x0 + 1, y1 - y0 + 1))
     }
 
-    pub fn debug_info_next_page(&mut self) {
-        self.debug_info_page += 1;
+    /// Identifies the pattern in the selection, or the whole world if nothing
+    /// is selected.
+    pub fn identify(&mut self) {
+        let cell_image = self.world.cell_image();
+        let points: Vec<Point> = match self.selection() {
+            Some(selection) => cell_image
+                .living_points
+                .into_iter()
+                .filter(|p| selection.contains(p))
+                .collect(),
+            None => cell_image.living_points,
+        };
+
+        self.pending_identification = None;
+        self.identification = Identification::identify(
+            &CellImage::from_points(&points),
+            &self.world.rules,
+            IDENTIFICATION_MAX_GENERATIONS,
+        );
+        self.message = Some(match &self.identification {
+            Some(identification) => format!("identified: {identification}"),
+            None => format!("not periodic within {IDENTIFICATION_MAX_GENERATIONS} generations"),
+        });
+    }
+
+    /// Shows the next of the debug info pages, or hides the debug info after
+    /// the last page.
+    pub fn debug_info_next_page(&mut self, page_count: usize) {
+        self.debug_info_page = (self.debug_info_page + 1) % (page_count + 1);
+    }
 
-        if self.debug_info_page > DEBUG_INFO_PAGE_TOTAL {
-            self.debug_info_page = 0;
+    /// Remembers a key press for the debug info, the oldest one is forgotten.
+    pub fn record_key(&mut self, key: &Key) {
+        if self.input_events.len() == INPUT_EVENTS_CAPACITY {
+            self.input_events.pop_front();
         }
+        self.input_events.push_back(key_name(key));
     }
 
     pub fn move_cursor_left(&mut self) {
@@ -124,8 +746,8 @@ impl State {
 
         self.cursor_pos.x += 1;
 
-        if self.cursor_pos.x >= self.screen_size.width() - 1 {
-            self.cursor_pos.x = self.screen_size.width() - 1;
+        if self.cursor_pos.x >= self.world.size.width() - 1 {
+            self.cursor_pos.x = self.world.size.width() - 1;
         }
     }
 
@@ -148,8 +770,118 @@ impl State {
 
         self.cursor_pos.y += 1;
 
-        if self.cursor_pos.y >= self.screen_size.height() - 1 {
-            self.cursor_pos.y = self.screen_size.height() - 1;
+        if self.cursor_pos.y >= self.world.size.height() - 1 {
+            self.cursor_pos.y = self.world.size.height() - 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::cell_setup::CellSetup;
+
+    #[test]
+    fn it_goes_only_forward_to_a_generation() {
+        let mut state = State::from(Args::default());
+        state.resize(&Point::new(20, 10));
+
+        state.goto(5);
+        while state.goto_generation.is_some() {
+            state.elapse_time();
         }
+        assert_eq!(state.world.generation, 5);
+        assert!(state.pause);
+
+        state.goto(2);
+        assert_eq!(state.world.generation, 5);
+        assert_eq!(
+            state.message.as_deref(),
+            Some("cannot go back to generation 2 from 5")
+        );
+    }
+
+    #[test]
+    fn it_identifies_the_cell_setup_in_the_background() {
+        let mut state = State::from(Args {
+            cell_setup: CellSetup::Special("OOO\n".to_string()),
+            paused: true,
+            ..Args::default()
+        });
+        state.resize(&Point::new(10, 10));
+        state.identify_in_background();
+
+        let start = Instant::now();
+        while !state.elapse_time() {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(
+            state.message.as_deref(),
+            Some("cell setup: oscillator with period 2")
+        );
+    }
+
+    #[test]
+    fn it_takes_the_census_in_the_background() {
+        let mut state = State::from(Args {
+            cell_setup: CellSetup::Special("OO\nOO\n".to_string()),
+            paused: true,
+            ..Args::default()
+        });
+        state.resize(&Point::new(10, 10));
+
+        state.toggle_census();
+        assert_eq!(
+            state.message.as_deref(),
+            Some("census: counting generation 0…")
+        );
+        let start = Instant::now();
+        while state.census.is_none() {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            state.elapse_time();
+        }
+        assert_eq!(state.message.as_deref(), Some("census: 1 objects"));
+
+        state.toggle_census();
+        assert!(state.census.is_none());
+    }
+
+    #[test]
+    fn it_restarts_period_detection_after_edits_and_rule_changes() {
+        let mut state = State::from(Args {
+            cell_setup: CellSetup::Special("OO\nOO\n".to_string()),
+            paused: true,
+            ..Args::default()
+        });
+        state.resize(&Point::new(10, 10));
+        state.step();
+        assert_eq!(state.period_detector.period, Some(1));
+
+        state.toggle_life_at_cursor();
+        assert_eq!(state.period_detector.period, None);
+
+        state.step();
+        state.step();
+        assert!(state.period_detector.period.is_some());
+        state.set_rules(Rules::parse("B36/S23").unwrap());
+        assert_eq!(state.period_detector.period, None);
+    }
+
+    #[test]
+    fn it_records_the_heatmap_while_it_is_hidden() {
+        let mut state = State::from(Args {
+            cell_setup: CellSetup::Special("OO\nOO\n".to_string()),
+            paused: true,
+            ..Args::default()
+        });
+        state.resize(&Point::new(10, 10));
+        state.step();
+        state.step();
+
+        state.set_heatmap_visible(true);
+        assert_eq!(state.world.heatmap.count(&Point::new(4, 4)), 2);
+        state.reset_heatmap();
+        assert_eq!(state.world.heatmap.count(&Point::new(4, 4)), 0);
     }
 }
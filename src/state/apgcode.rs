@@ -0,0 +1,169 @@
+use term2d::model::point::Point;
+
+use super::cell_image::CellImage;
+
+const WECHSLER_CHARS: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+const WECHSLER_RUN_CHARS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const WECHSLER_STRIP_HEIGHT: i32 = 5;
+
+/// Names of the most common objects, keyed by their apgcode, from the most to
+/// the least common in soups.
+pub const OBJECT_NAMES: [(&str, &str); 19] = [
+    ("xs4_33", "block"),
+    ("xp2_7", "blinker"),
+    ("xs6_696", "beehive"),
+    ("xq4_153", "glider"),
+    ("xs7_2596", "loaf"),
+    ("xs5_253", "boat"),
+    ("xs6_356", "ship"),
+    ("xs4_252", "tub"),
+    ("xs8_6996", "pond"),
+    ("xs7_25ac", "long boat"),
+    ("xp2_7e", "toad"),
+    ("xs6_25a4", "barge"),
+    ("xp2_318c", "beacon"),
+    ("xs8_69ic", "mango"),
+    ("xs6_39c", "snake"),
+    ("xs7_178c", "eater 1"),
+    ("xs12_g8o653z11", "ship-tie"),
+    ("xq4_6frc", "lightweight spaceship"),
+    ("xp15_4r4z4r4", "pentadecathlon"),
+];
+
+pub fn object_name(apgcode: &str) -> Option<&'static str> {
+    OBJECT_NAMES
+        .iter()
+        .find(|(code, _)| *code == apgcode)
+        .map(|(_, name)| *name)
+}
+
+/// Encodes the points in the extended Wechsler format: the pattern is cut into
+/// strips of 5 rows, every column of a strip becomes one character and runs of
+/// empty columns are compressed.
+pub fn wechsler(points: &[Point]) -> String {
+    let cell_image = CellImage::from_points(points);
+    let width = cell_image.size.width();
+    let strips = (cell_image.size.height() + WECHSLER_STRIP_HEIGHT - 1) / WECHSLER_STRIP_HEIGHT;
+
+    let mut columns = vec![vec![0_u8; width as usize]; strips as usize];
+    for p in &cell_image.living_points {
+        let strip = p.y / WECHSLER_STRIP_HEIGHT;
+        columns[strip as usize][p.x as usize] |= 1 << (p.y % WECHSLER_STRIP_HEIGHT);
+    }
+
+    let encoded_strips: Vec<String> = columns
+        .into_iter()
+        .map(|mut strip| {
+            while strip.last() == Some(&0) {
+                strip.pop();
+            }
+            compress_zeros(
+                &strip
+                    .into_iter()
+                    .map(|column| WECHSLER_CHARS[column as usize] as char)
+                    .collect::<String>(),
+            )
+        })
+        .collect();
+
+    encoded_strips.join("z")
+}
+
+fn compress_zeros(s: &str) -> String {
+    let mut compressed = String::new();
+    let mut zeros = 0;
+
+    let flush = |compressed: &mut String, zeros: &mut usize| {
+        while *zeros > 0 {
+            match *zeros {
+                1 => compressed.push('0'),
+                2 => compressed.push('w'),
+                3 => compressed.push('x'),
+                n => {
+                    let run = n.min(39);
+                    compressed.push('y');
+                    compressed.push(WECHSLER_RUN_CHARS[run - 4] as char);
+                    *zeros -= run;
+                    continue;
+                }
+            }
+            *zeros = 0;
+        }
+    };
+
+    for c in s.chars() {
+        if c == '0' {
+            zeros += 1;
+        } else {
+            flush(&mut compressed, &mut zeros);
+            compressed.push(c);
+        }
+    }
+    flush(&mut compressed, &mut zeros);
+
+    compressed
+}
+
+/// The 8 rotations and reflections of the points.
+fn orientations(points: &[Point]) -> Vec<Vec<Point>> {
+    let transforms: [fn(&Point) -> Point; 8] = [
+        |p| Point::new(p.x, p.y),
+        |p| Point::new(-p.x, p.y),
+        |p| Point::new(p.x, -p.y),
+        |p| Point::new(-p.x, -p.y),
+        |p| Point::new(p.y, p.x),
+        |p| Point::new(-p.y, p.x),
+        |p| Point::new(p.y, -p.x),
+        |p| Point::new(-p.y, -p.x),
+    ];
+
+    transforms
+        .iter()
+        .map(|transform| points.iter().map(transform).collect())
+        .collect()
+}
+
+/// Chooses the shortest and then lexicographically smallest Wechsler code of
+/// all phases in all orientations.
+pub fn canonical_wechsler(phases: &[Vec<Point>]) -> String {
+    phases
+        .iter()
+        .flat_map(|phase| orientations(phase))
+        .map(|points| wechsler(&points))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn points(pattern: &str) -> Vec<Point> {
+        CellImage::from(pattern).living_points
+    }
+
+    #[test]
+    fn it_encodes_in_the_wechsler_format() {
+        assert_eq!(wechsler(&points("OO\nOO")), "33");
+        assert_eq!(wechsler(&points("O\n.\n.\n.\n.\nO")), "1z1");
+        assert_eq!(wechsler(&points("O.....O")), "1y11");
+        assert_eq!(wechsler(&points("O..O")), "1w1");
+        assert_eq!(wechsler(&points(&format!("O{}O", ".".repeat(40)))), "1yz01");
+    }
+
+    #[test]
+    fn it_chooses_the_canonical_orientation() {
+        assert_eq!(canonical_wechsler(&[points("OOO")]), "7");
+        assert_eq!(canonical_wechsler(&[points(".OO.\nO..O\n.OO.")]), "696");
+        assert_eq!(
+            canonical_wechsler(&[points(".OO.\nO..O\n.O.O\n..O.")]),
+            "2596"
+        );
+    }
+
+    #[test]
+    fn it_looks_up_object_names() {
+        assert_eq!(object_name("xq4_153"), Some("glider"));
+        assert_eq!(object_name("xs4_0"), None);
+    }
+}
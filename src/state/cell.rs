@@ -1,16 +1,9 @@
 use term2d::model::color::Color;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct Cell {
+    /// The number of generations a living cell has survived.
+    pub age: u64,
     pub alive: bool,
     pub color: Color,
 }
-
-impl Default for Cell {
-    fn default() -> Self {
-        Self {
-            alive: false,
-            color: Color::default(),
-        }
-    }
-}
@@ -1,8 +1,20 @@
+use regex::Regex;
 use term2d::model::point::Point;
 
 use super::cell_setup::CellSetup;
+use super::rules::Rules;
 
 const DEAD_CELL_CHARS: &str = ". ";
+const RLE_LINE_LENGTH: usize = 70;
+
+/// The text formats in which patterns are written.
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum PatternFormat {
+    /// Only the cells drawn with the alive and dead characters
+    Ascii,
+    Plaintext,
+    Rle,
+}
 
 #[derive(Debug)]
 pub struct CellImage {
@@ -12,12 +24,16 @@ pub struct CellImage {
 
 impl From<&str> for CellImage {
     fn from(s: &str) -> Self {
+        if let Some(cell_image) = CellImage::from_rle(s) {
+            return cell_image;
+        }
+
         let mut living_points = Vec::new();
         let mut width = 0;
 
         let mut y = 0;
-        for line in s.split("\n") {
-            if line.starts_with("!") {
+        for line in s.split('\n') {
+            if line.starts_with('!') {
                 continue;
             }
 
@@ -25,12 +41,10 @@ impl From<&str> for CellImage {
                 width = line.len() as i32;
             }
 
-            let mut x = 0;
-            for c in line.chars() {
-                if DEAD_CELL_CHARS.find(c).is_none() {
-                    living_points.push(Point::new(x, y));
+            for (x, c) in line.chars().enumerate() {
+                if !DEAD_CELL_CHARS.contains(c) {
+                    living_points.push(Point::new(x as i32, y));
                 }
-                x += 1;
             }
             y += 1;
         }
@@ -42,9 +56,247 @@ impl From<&str> for CellImage {
     }
 }
 
-impl From<CellSetup> for CellImage {
-    fn from(cell_setup: CellSetup) -> Self {
-        let s: String = cell_setup.into();
-        CellImage::from(s.as_str())
+impl CellImage {
+    pub fn from_cell_setup(cell_setup: &CellSetup, seed: u64) -> Self {
+        CellImage::from(cell_setup.generate(seed).as_str())
+    }
+
+    /// Creates the smallest cell image containing all the points.
+    pub fn from_points(points: &[Point]) -> Self {
+        if points.is_empty() {
+            return CellImage {
+                living_points: Vec::new(),
+                size: Point::new(0, 0),
+            };
+        }
+
+        let min_x = points.iter().map(|p| p.x).min().unwrap();
+        let max_x = points.iter().map(|p| p.x).max().unwrap();
+        let min_y = points.iter().map(|p| p.y).min().unwrap();
+        let max_y = points.iter().map(|p| p.y).max().unwrap();
+        let origin = Point::new(min_x, min_y);
+
+        let mut living_points: Vec<Point> = points.iter().map(|p| p - &origin).collect();
+        living_points.sort_by_key(|p| (p.y, p.x));
+
+        CellImage {
+            living_points,
+            size: Point::new(max_x - min_x + 1, max_y - min_y + 1),
+        }
+    }
+
+    /// Parses the run length encoded format, returns `None` if the header line
+    /// is missing.
+    fn from_rle(s: &str) -> Option<Self> {
+        let header_regex = Regex::new(r"^x\s*=\s*(\d+)\s*,\s*y\s*=\s*(\d+)").unwrap();
+
+        let mut lines = s
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        let header = header_regex.captures(lines.next()?)?;
+        let width: i32 = header[1].parse().ok()?;
+        let height: i32 = header[2].parse().ok()?;
+
+        let mut living_points = Vec::new();
+        let mut point = Point::new(0, 0);
+        let mut run_count = 0;
+
+        'lines: for line in lines {
+            for c in line.chars() {
+                let count = if run_count == 0 { 1 } else { run_count };
+                match c {
+                    '0'..='9' => {
+                        run_count = 10 * run_count + c.to_digit(10).unwrap() as i32;
+                        continue;
+                    }
+                    'b' | '.' => point.x += count,
+                    '$' => {
+                        point.x = 0;
+                        point.y += count;
+                    }
+                    '!' => break 'lines,
+                    c if c.is_whitespace() => continue,
+                    _ => {
+                        for _ in 0..count {
+                            living_points.push(point.clone());
+                            point.x += 1;
+                        }
+                    }
+                }
+                run_count = 0;
+            }
+        }
+
+        Some(CellImage {
+            living_points,
+            size: Point::new(width, height),
+        })
+    }
+
+    fn is_alive_grid(&self) -> Vec<Vec<bool>> {
+        let mut grid = vec![vec![false; self.size.width() as usize]; self.size.height() as usize];
+        for p in &self.living_points {
+            if p.is_contained(&self.size) {
+                grid[p.y as usize][p.x as usize] = true;
+            }
+        }
+        grid
+    }
+
+    pub fn to_pattern(
+        &self,
+        format: PatternFormat,
+        rules: &Rules,
+        comments: &[String],
+        cell_chars: [&str; 2],
+    ) -> String {
+        match format {
+            PatternFormat::Ascii => self.to_ascii(cell_chars[0], cell_chars[1]),
+            PatternFormat::Plaintext => self.to_plaintext(comments),
+            PatternFormat::Rle => self.to_rle(rules, comments),
+        }
+    }
+
+    pub fn to_plaintext(&self, comments: &[String]) -> String {
+        let mut s = String::new();
+
+        for comment in comments {
+            s.push_str(&format!("!{comment}\n"));
+        }
+
+        for row in self.is_alive_grid() {
+            for alive in row {
+                s.push(if alive { 'O' } else { '.' });
+            }
+            s.push('\n');
+        }
+
+        s
+    }
+
+    /// Draws the cells with the specified characters, without any comments so
+    /// that the output can be piped into other text.
+    pub fn to_ascii(&self, char_alive: &str, char_dead: &str) -> String {
+        let mut s = String::new();
+
+        for row in self.is_alive_grid() {
+            for alive in row {
+                s.push_str(if alive { char_alive } else { char_dead });
+            }
+            s.push('\n');
+        }
+
+        s
+    }
+
+    pub fn to_rle(&self, rules: &Rules, comments: &[String]) -> String {
+        let mut s = String::new();
+
+        for comment in comments {
+            s.push_str(&format!("#C {comment}\n"));
+        }
+        s.push_str(&format!(
+            "x = {}, y = {}, rule = {}\n",
+            self.size.width(),
+            self.size.height(),
+            rules,
+        ));
+
+        let mut runs: Vec<(usize, char)> = Vec::new();
+        let mut push_run = |count: usize, tag: char| match runs.last_mut() {
+            Some((last_count, last_tag)) if *last_tag == tag => *last_count += count,
+            _ => runs.push((count, tag)),
+        };
+
+        for (y, row) in self.is_alive_grid().into_iter().enumerate() {
+            if y > 0 {
+                push_run(1, '$');
+            }
+
+            let last_alive = row.iter().rposition(|&alive| alive);
+            for &alive in row.iter().take(last_alive.map_or(0, |x| x + 1)) {
+                push_run(1, if alive { 'o' } else { 'b' });
+            }
+        }
+
+        while runs.last().map(|run| run.1) == Some('$') {
+            runs.pop();
+        }
+
+        let mut line = String::new();
+        for (count, tag) in runs {
+            let item = if count == 1 {
+                tag.to_string()
+            } else {
+                format!("{count}{tag}")
+            };
+
+            if line.len() + item.len() > RLE_LINE_LENGTH {
+                s.push_str(&line);
+                s.push('\n');
+                line.clear();
+            }
+            line.push_str(&item);
+        }
+        s.push_str(&line);
+        s.push_str("!\n");
+
+        s
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GLIDER_RLE: &str = "#C a glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+
+    #[test]
+    fn it_parses_plaintext() {
+        let cell_image = CellImage::from("!Name: glider\n.O.\n..O\nOOO");
+        assert_eq!(cell_image.size, Point::new(3, 3));
+        assert_eq!(cell_image.living_points.len(), 5);
+    }
+
+    #[test]
+    fn it_parses_rle() {
+        let cell_image = CellImage::from(GLIDER_RLE);
+        assert_eq!(cell_image.size, Point::new(3, 3));
+        assert_eq!(
+            cell_image.living_points,
+            vec![
+                Point::new(1, 0),
+                Point::new(2, 1),
+                Point::new(0, 2),
+                Point::new(1, 2),
+                Point::new(2, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_writes_rle() {
+        let cell_image = CellImage::from(GLIDER_RLE);
+        let rules = Rules::parse("B3/S23").unwrap();
+        let rle = cell_image.to_rle(&rules, &["a glider".to_string()]);
+        assert_eq!(rle, GLIDER_RLE);
+    }
+
+    #[test]
+    fn it_writes_plaintext() {
+        let cell_image = CellImage::from_points(&[Point::new(5, 5), Point::new(6, 7)]);
+        assert_eq!(cell_image.to_plaintext(&[]), "O.\n..\n.O\n");
+        assert_eq!(cell_image.to_ascii("[]", "  "), "[]  \n    \n  []\n");
+    }
+
+    #[test]
+    fn it_writes_empty_rows_in_rle() {
+        let cell_image = CellImage::from("O\n.\n.\nO");
+        let rules = Rules::parse("B3/S23").unwrap();
+        assert_eq!(
+            cell_image.to_rle(&rules, &[]),
+            "x = 1, y = 4, rule = B3/S23\no3$o!\n"
+        );
     }
 }
@@ -34,22 +34,33 @@ pub enum CellSetup {
     Blank,
     RPentonimo,
     Termgol,
+    RandomPercentage(u8),
+    RectSoup(i32, i32),
     Special(String),
 }
 
-impl From<CellSetup> for String {
-    fn from(cell_setup: CellSetup) -> Self {
-        match cell_setup {
+impl CellSetup {
+    /// Creates the textual representation of the cell setup, random setups
+    /// are generated deterministically from the seed.
+    pub fn generate(&self, seed: u64) -> String {
+        match self {
             CellSetup::Acorn => ACORN.to_string(),
             CellSetup::Blank => BLANK.to_string(),
             CellSetup::RPentonimo => R_PENTONIMO.to_string(),
             CellSetup::Termgol => TERMGOL.to_string(),
-            CellSetup::Special(s) => s,
+            CellSetup::RandomPercentage(percentage) => Self::random_percentage(*percentage, seed),
+            CellSetup::RectSoup(width, height) => Self::rect_soup(*width, *height, seed),
+            CellSetup::Special(s) => s.clone(),
         }
     }
-}
 
-impl CellSetup {
+    pub fn is_random(&self) -> bool {
+        matches!(
+            self,
+            CellSetup::RandomPercentage(_) | CellSetup::RectSoup(_, _)
+        )
+    }
+
     pub fn parse(s: &str) -> Result<CellSetup, Error> {
         let cell_setup = match s {
             "acorn" => CellSetup::Acorn,
@@ -71,10 +82,10 @@ impl CellSetup {
 
         if let Some(random_captures) = random_captures_iter.next() {
             let random_percent: u8 = random_captures[1].parse().unwrap();
-            Ok(CellSetup::random_percentage(random_percent))
+            Ok(CellSetup::RandomPercentage(random_percent))
         } else if let Some(soup_captures) = soup_captures_iter.next() {
             let soup_size: i32 = soup_captures[1].parse().unwrap();
-            Ok(CellSetup::rect_soup(soup_size, soup_size))
+            Ok(CellSetup::RectSoup(soup_size, soup_size))
         } else {
             let mut file = File::open(s)?;
             let mut file_content = String::new();
@@ -83,10 +94,10 @@ impl CellSetup {
         }
     }
 
-    pub fn rect_soup(width: i32, height: i32) -> CellSetup {
+    fn rect_soup(width: i32, height: i32, seed: u64) -> String {
         let mut soup = String::new();
 
-        let mut rng = WyRand::new();
+        let mut rng = WyRand::new_seed(seed);
         for _y in 0..height {
             for _x in 0..width {
                 if rng.generate_range(0_u8..=1) == 0 {
@@ -98,13 +109,13 @@ impl CellSetup {
             soup.push('\n');
         }
 
-        CellSetup::Special(soup)
+        soup
     }
 
-    pub fn random_percentage(percentage: u8) -> CellSetup {
+    fn random_percentage(percentage: u8, seed: u64) -> String {
         let mut soup = String::new();
 
-        let mut rng = WyRand::new();
+        let mut rng = WyRand::new_seed(seed);
         for _y in 0..200 {
             for _x in 0..300 {
                 if rng.generate_range(0_u8..=100) >= percentage {
@@ -116,6 +127,24 @@ impl CellSetup {
             soup.push('\n');
         }
 
-        CellSetup::Special(soup)
+        soup
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_generates_the_same_soup_for_the_same_seed() {
+        let soup = CellSetup::parse("soup16").unwrap();
+        assert_eq!(soup.generate(42), soup.generate(42));
+        assert_ne!(soup.generate(42), soup.generate(43));
+    }
+
+    #[test]
+    fn it_tells_random_from_fixed_setups() {
+        assert!(CellSetup::parse("random30").unwrap().is_random());
+        assert!(!CellSetup::parse("acorn").unwrap().is_random());
     }
 }
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use term2d::model::point::Point;
+
+use crate::common::point_hash::PointHashSet;
+
+use super::apgcode;
+use super::cell_image::CellImage;
+use super::identification::Classification;
+use super::identification::Identification;
+use super::identification::IDENTIFICATION_MAX_GENERATIONS;
+use super::rules::Rules;
+use super::universe::Universe;
+
+pub const UNIDENTIFIED: &str = "unidentified";
+
+/// Groups of cells with bounding boxes closer than this may influence each
+/// other.
+const INTERACTION_DISTANCE: i32 = 4;
+const INTERACTION_GENERATIONS: u64 = 64;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CensusEntry {
+    pub apgcode: String,
+    pub count: usize,
+}
+
+impl CensusEntry {
+    pub fn name(&self) -> Option<&'static str> {
+        apgcode::object_name(&self.apgcode)
+    }
+}
+
+/// The objects of a generation, most common first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Census {
+    pub entries: Vec<CensusEntry>,
+    pub generation: u64,
+}
+
+impl Census {
+    /// Splits the living cells into objects and counts them by their apgcode.
+    /// With a `torus` size objects may wrap around the edges.
+    pub fn take(
+        cell_image: &CellImage,
+        torus: Option<&Point>,
+        rules: &Rules,
+        generation: u64,
+    ) -> Self {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        count_objects(&cell_image.living_points, torus, rules, &mut counts);
+
+        let mut entries: Vec<CensusEntry> = counts
+            .into_iter()
+            .map(|(apgcode, count)| CensusEntry { apgcode, count })
+            .collect();
+        entries.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.apgcode.cmp(&b.apgcode))
+        });
+
+        Self {
+            entries,
+            generation,
+        }
+    }
+
+    pub fn object_count(&self) -> usize {
+        self.entries.iter().map(|entry| entry.count).sum()
+    }
+
+    /// One line per kind of object, e.g. `12 block (xs4_33)`.
+    pub fn lines(&self) -> Vec<String> {
+        let width = self
+            .entries
+            .first()
+            .map_or(1, |entry| entry.count.to_string().len());
+
+        self.entries
+            .iter()
+            .map(|entry| match entry.name() {
+                Some(name) => format!("{:>width$} {name} ({})", entry.count, entry.apgcode),
+                None => format!("{:>width$} {}", entry.count, entry.apgcode),
+            })
+            .collect()
+    }
+}
+
+/// Splits the points into objects. Groups of 8-connected cells are merged
+/// when they interact, e.g. the two halves of a toad in one of its phases.
+pub fn objects(points: &[Point], torus: Option<&Point>, rules: &Rules) -> Vec<Vec<Point>> {
+    let mut objects = connected_groups(points, torus);
+
+    let mut i = 0;
+    while i < objects.len() {
+        let mut merged = false;
+        let mut j = i + 1;
+        while j < objects.len() {
+            match nearest_image(&objects[i], &objects[j], torus) {
+                Some(other) if belong_together(&objects[i], &other, rules) => {
+                    objects.swap_remove(j);
+                    objects[i].extend(other);
+                    merged = true;
+                }
+                _ => j += 1,
+            }
+        }
+
+        // a merged object may interact with objects it was already compared to
+        if !merged {
+            i += 1;
+        }
+    }
+
+    objects
+}
+
+/// Splits the points into groups of 8-connected cells.
+fn connected_groups(points: &[Point], torus: Option<&Point>) -> Vec<Vec<Point>> {
+    let wrap = |p: &Point| match torus {
+        Some(size) => Point::new(p.x.rem_euclid(size.x), p.y.rem_euclid(size.y)),
+        None => p.clone(),
+    };
+
+    let living: HashSet<Point> = points.iter().map(wrap).collect();
+    let mut visited: HashSet<Point> = HashSet::new();
+    let mut groups = Vec::new();
+
+    for start in points.iter().map(wrap) {
+        if !visited.insert(start.clone()) {
+            continue;
+        }
+
+        // the points of a group are unwrapped, so it stays contiguous even if
+        // it crosses the edges of a torus
+        let mut group = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(p) = queue.pop_front() {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let neighbour = Point::new(p.x + dx, p.y + dy);
+                    let wrapped = wrap(&neighbour);
+                    if living.contains(&wrapped) && visited.insert(wrapped) {
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+            group.push(p);
+        }
+        groups.push(group);
+    }
+
+    groups
+}
+
+fn bounding_box(points: &[Point]) -> (Point, Point) {
+    let min = Point::new(
+        points.iter().map(|p| p.x).min().unwrap_or(0),
+        points.iter().map(|p| p.y).min().unwrap_or(0),
+    );
+    let max = Point::new(
+        points.iter().map(|p| p.x).max().unwrap_or(0),
+        points.iter().map(|p| p.y).max().unwrap_or(0),
+    );
+    (min, max)
+}
+
+/// Returns the points of `b`, on a torus shifted to the image closest to `a`,
+/// if they are within the interaction distance of `a`.
+fn nearest_image(a: &[Point], b: &[Point], torus: Option<&Point>) -> Option<Vec<Point>> {
+    let (a_min, a_max) = bounding_box(a);
+    let (b_min, b_max) = bounding_box(b);
+    let gap = |shift: &Point| {
+        let dx = (b_min.x + shift.x - a_max.x).max(a_min.x - b_max.x - shift.x);
+        let dy = (b_min.y + shift.y - a_max.y).max(a_min.y - b_max.y - shift.y);
+        dx.max(dy)
+    };
+
+    let shifts: Vec<Point> = match torus {
+        Some(size) => (-1..=1)
+            .flat_map(|i| (-1..=1).map(move |j| Point::new(i * size.x, j * size.y)))
+            .collect(),
+        None => vec![Point::new(0, 0)],
+    };
+    let shift = shifts.into_iter().min_by_key(|shift| gap(shift))?;
+    if gap(&shift) > INTERACTION_DISTANCE {
+        return None;
+    }
+
+    Some(b.iter().map(|p| p + &shift).collect())
+}
+
+/// Checks whether the groups evolve differently together than on their own,
+/// or whether one of them dies out on its own, since such sparks belong to
+/// the object next to them.
+fn belong_together(a: &[Point], b: &[Point], rules: &Rules) -> bool {
+    let universe = |points: &[Point]| Universe {
+        generation: 0,
+        living_points: points.iter().cloned().collect(),
+        rules: rules.clone(),
+    };
+    let mut together = universe(&[a, b].concat());
+    let mut a = universe(a);
+    let mut b = universe(b);
+
+    for _ in 0..INTERACTION_GENERATIONS {
+        together.update();
+        a.update();
+        b.update();
+
+        let apart: PointHashSet = a.living_points.union(&b.living_points).cloned().collect();
+        if together.living_points != apart
+            || a.living_points.is_empty()
+            || b.living_points.is_empty()
+        {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Counts the objects by their apgcode. Objects which evolve before they
+/// become periodic, e.g. a glider about to hit a block, may fall apart into
+/// several objects which are counted on their own.
+fn count_objects(
+    points: &[Point],
+    torus: Option<&Point>,
+    rules: &Rules,
+    counts: &mut HashMap<String, usize>,
+) {
+    for object in objects(points, torus, rules) {
+        let cell_image = CellImage::from_points(&object);
+        let identification =
+            Identification::identify(&cell_image, rules, IDENTIFICATION_MAX_GENERATIONS);
+
+        let mut universe = Universe::new(&cell_image, rules.clone());
+        if let Some(identification) = &identification {
+            while universe.generation < identification.since {
+                universe.update();
+            }
+        }
+
+        let settled: Vec<Point> = universe.living_points.iter().cloned().collect();
+        if universe.generation > 0 && objects(&settled, None, rules).len() > 1 {
+            count_objects(&settled, None, rules, counts);
+        } else {
+            *counts
+                .entry(classify(universe, identification))
+                .or_insert(0) += 1;
+        }
+    }
+}
+
+/// Computes the apgcode of an object in its periodic phase, e.g. `xs4_33` for
+/// a block, or `UNIDENTIFIED` if it does not become periodic on its own.
+fn classify(mut universe: Universe, identification: Option<Identification>) -> String {
+    let (prefix, period) = match identification.map(|i| i.classification) {
+        Some(Classification::StillLife) => ("xs", 1),
+        Some(Classification::Oscillator { period }) => ("xp", period),
+        Some(Classification::Spaceship { period, .. }) => ("xq", period),
+        _ => return UNIDENTIFIED.to_string(),
+    };
+
+    let mut phases = Vec::new();
+    for _ in 0..period {
+        phases.push(universe.living_points.iter().cloned().collect::<Vec<_>>());
+        universe.update();
+    }
+
+    let code = apgcode::canonical_wechsler(&phases);
+    if prefix == "xs" {
+        format!("{prefix}{}_{code}", phases[0].len())
+    } else {
+        format!("{prefix}{period}_{code}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn conway() -> Rules {
+        Rules::parse("B3/S23").unwrap()
+    }
+
+    fn apgcode(pattern: &str) -> String {
+        let census = Census::take(&CellImage::from(pattern), None, &conway(), 0);
+        assert_eq!(census.object_count(), 1);
+        census.entries[0].apgcode.clone()
+    }
+
+    #[test]
+    fn it_computes_the_apgcodes_of_common_objects() {
+        assert_eq!(apgcode("OO\nOO"), "xs4_33");
+        assert_eq!(apgcode("O\nO\nO"), "xp2_7");
+        assert_eq!(apgcode(".OO.\nO..O\n.OO."), "xs6_696");
+        assert_eq!(apgcode("O.O\n.OO\n.O."), "xq4_153");
+        assert_eq!(apgcode(".OO.\nO..O\n.O.O\n..O."), "xs7_2596");
+        assert_eq!(apgcode("OO.\nO.O\n.O."), "xs5_253");
+        assert_eq!(apgcode("OO.\nO.O\n.OO"), "xs6_356");
+        assert_eq!(apgcode(".O.\nO.O\n.O."), "xs4_252");
+        assert_eq!(apgcode(".OO.\nO..O\nO..O\n.OO."), "xs8_6996");
+        assert_eq!(apgcode(".OOO\nOOO."), "xp2_7e");
+        assert_eq!(apgcode(".O..\nO..O\nO..O\n..O."), "xp2_7e");
+        assert_eq!(apgcode("OO..\nOO..\n..OO\n..OO"), "xp2_318c");
+        assert_eq!(apgcode("OO..\nO.O.\n..O.\n..OO"), "xs7_178c");
+        assert_eq!(apgcode(".O..O\nO....\nO...O\nOOOO."), "xq4_6frc");
+    }
+
+    #[test]
+    fn it_does_not_identify_objects_which_die_out() {
+        assert_eq!(apgcode("OO"), UNIDENTIFIED);
+    }
+
+    #[test]
+    fn it_splits_objects_which_fall_apart() {
+        let t_tetromino = CellImage::from(".O.\nOOO");
+        let census = Census::take(&t_tetromino, None, &conway(), 0);
+        assert_eq!(census.lines(), ["4 blinker (xp2_7)"]);
+    }
+
+    #[test]
+    fn it_splits_cells_into_objects() {
+        let points = CellImage::from("OO....O\nOO....O\n......O\n.......").living_points;
+        assert_eq!(connected_groups(&points, None).len(), 2);
+        assert_eq!(connected_groups(&points, Some(&Point::new(7, 4))).len(), 1);
+    }
+
+    #[test]
+    fn it_merges_interacting_groups_and_sparks() {
+        let points = CellImage::from("OO.....\nOO..OOO").living_points;
+        assert_eq!(objects(&points, None, &conway()).len(), 2);
+
+        let points = CellImage::from("OO..\nOO..\n...O").living_points;
+        assert_eq!(objects(&points, None, &conway()).len(), 1);
+
+        let points = CellImage::from("OO...\nOO.OO").living_points;
+        assert_eq!(objects(&points, None, &conway()).len(), 1);
+    }
+
+    #[test]
+    fn it_counts_objects_most_common_first() {
+        let cell_image = CellImage::from("OO.OO...O\nOO.OO...O\n........O");
+        let census = Census::take(&cell_image, None, &conway(), 0);
+        assert_eq!(census.object_count(), 3);
+        assert_eq!(census.lines(), ["2 block (xs4_33)", "1 blinker (xp2_7)"]);
+    }
+}
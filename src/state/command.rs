@@ -0,0 +1,121 @@
+use term2d::model::rgba::Rgba;
+
+use crate::common::color::RgbaParser;
+
+use super::rules::Rules;
+
+/// Commands which can be entered at runtime after pressing ':'.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Age(Option<bool>),
+    Alive(Rgba),
+    Dead(Rgba),
+    Fading(i32),
+    Fps(u16),
+    Goto(u64),
+    Heatmap(Option<bool>),
+    Rainbow(Option<bool>),
+    ResetHeatmap,
+    Rules(Rules),
+}
+
+impl Command {
+    pub fn parse(command_line: &str) -> Result<Command, String> {
+        let mut words = command_line.split_whitespace();
+        let name = words.next().ok_or_else(|| "empty command".to_string())?;
+        let argument = words.next();
+
+        if words.next().is_some() {
+            return Err(format!("too many arguments for '{name}'"));
+        }
+
+        let command = match name {
+            "age" => Command::Age(Self::parse_switch(argument)?),
+            "alive" => Command::Alive(Self::parse_color(name, argument)?),
+            "dead" => Command::Dead(Self::parse_color(name, argument)?),
+            "fading" => Command::Fading(Self::parse_number(name, argument)?),
+            "fps" => Command::Fps(Self::parse_number(name, argument)?),
+            "goto" => Command::Goto(Self::parse_number(name, argument)?),
+            "heatmap" if argument == Some("reset") => Command::ResetHeatmap,
+            "heatmap" => Command::Heatmap(Self::parse_switch(argument)?),
+            "rainbow" => Command::Rainbow(Self::parse_switch(argument)?),
+            "rules" => Command::Rules(
+                Rules::parse(Self::require(name, argument)?)
+                    .map_err(|_| "invalid rules, expected e.g. 'B3/S23'".to_string())?,
+            ),
+            _ => return Err(format!("unknown command '{name}'")),
+        };
+
+        Ok(command)
+    }
+
+    fn require<'a>(name: &str, argument: Option<&'a str>) -> Result<&'a str, String> {
+        argument.ok_or_else(|| format!("'{name}' requires an argument"))
+    }
+
+    /// Parses an optional 'on' or 'off', `None` toggles.
+    fn parse_switch(argument: Option<&str>) -> Result<Option<bool>, String> {
+        match argument {
+            None => Ok(None),
+            Some("on") => Ok(Some(true)),
+            Some("off") => Ok(Some(false)),
+            Some(a) => Err(format!("expected 'on' or 'off', got '{a}'")),
+        }
+    }
+
+    fn parse_color(name: &str, argument: Option<&str>) -> Result<Rgba, String> {
+        RgbaParser::parse(Self::require(name, argument)?)
+            .map_err(|_| "invalid color, expected e.g. '#ff0000'".to_string())
+    }
+
+    fn parse_number<T: std::str::FromStr>(name: &str, argument: Option<&str>) -> Result<T, String> {
+        let argument = Self::require(name, argument)?;
+        argument
+            .parse()
+            .map_err(|_| format!("invalid number '{argument}' for '{name}'"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_a_rules_command() {
+        let command = Command::parse("rules B36/S23").unwrap();
+        assert_eq!(command, Command::Rules(Rules::parse("B36/S23").unwrap()));
+    }
+
+    #[test]
+    fn it_parses_color_and_number_commands() {
+        assert_eq!(
+            Command::parse("alive #ff0000").unwrap(),
+            Command::Alive(Rgba::red())
+        );
+        assert_eq!(Command::parse("fps 25").unwrap(), Command::Fps(25));
+        assert_eq!(Command::parse("goto 1000").unwrap(), Command::Goto(1000));
+        assert_eq!(Command::parse(" fading -3 ").unwrap(), Command::Fading(-3));
+        assert_eq!(Command::parse("rainbow").unwrap(), Command::Rainbow(None));
+        assert_eq!(
+            Command::parse("rainbow off").unwrap(),
+            Command::Rainbow(Some(false))
+        );
+        assert_eq!(Command::parse("age on").unwrap(), Command::Age(Some(true)));
+        assert_eq!(Command::parse("heatmap").unwrap(), Command::Heatmap(None));
+        assert_eq!(
+            Command::parse("heatmap reset").unwrap(),
+            Command::ResetHeatmap
+        );
+    }
+
+    #[test]
+    fn it_fails_on_invalid_commands() {
+        assert!(Command::parse("").is_err());
+        assert!(Command::parse("nonsense").is_err());
+        assert!(Command::parse("fps").is_err());
+        assert!(Command::parse("fps fast").is_err());
+        assert!(Command::parse("goto -1").is_err());
+        assert!(Command::parse("rules B3/S23 B36/S23").is_err());
+        assert!(Command::parse("age young").is_err());
+    }
+}
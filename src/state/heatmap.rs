@@ -0,0 +1,159 @@
+use std::cmp::max;
+use std::cmp::min;
+
+use term2d::model::point::Point;
+
+use crate::common::point_hash::PointHashMap;
+
+/// Counts for every cell of the world how many generations it has been alive
+/// or died since the last reset. The counts are indexed like the cells.
+#[derive(Clone, Debug)]
+pub struct Heatmap {
+    counts: Vec<u64>,
+    maximum: u64,
+    size: Point,
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Self {
+            counts: Vec::new(),
+            maximum: 0,
+            size: Point::new(0, 0),
+        }
+    }
+}
+
+impl Heatmap {
+    /// Changes the size of the heatmap and keeps the counts of the cells that
+    /// fit.
+    pub fn resize(&mut self, size: &Point) {
+        let old_size = std::mem::replace(&mut self.size, size.clone());
+        let old_counts = std::mem::replace(
+            &mut self.counts,
+            vec![0; (size.width() * size.height()) as usize],
+        );
+
+        for y in 0..min(old_size.height(), size.height()) {
+            for x in 0..min(old_size.width(), size.width()) {
+                self.counts[(size.width() * y + x) as usize] =
+                    old_counts[(old_size.width() * y + x) as usize];
+            }
+        }
+        self.maximum = self.counts.iter().copied().max().unwrap_or(0);
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.maximum = 0;
+    }
+
+    pub fn record(&mut self, i: usize) {
+        self.counts[i] += 1;
+        self.maximum = max(self.maximum, self.counts[i]);
+    }
+
+    pub fn count(&self, p: &Point) -> u64 {
+        if !p.is_contained(&self.size) {
+            return 0;
+        }
+
+        self.counts[(self.size.width() * p.y + p.x) as usize]
+    }
+
+    /// Maps the count of a cell logarithmically to the range 0..=1.
+    pub fn intensity(&self, p: &Point) -> f32 {
+        if self.maximum == 0 {
+            return 0.0;
+        }
+
+        (self.count(p) as f32).ln_1p() / (self.maximum as f32).ln_1p()
+    }
+
+    /// Formats the counts as comma separated rows.
+    pub fn to_csv(&self) -> String {
+        to_csv(&Point::new(0, 0), &self.size, |p| self.count(p))
+    }
+}
+
+/// Counts cell activity like the `Heatmap`, but in the unbounded universe
+/// where only the active cells are stored.
+#[derive(Clone, Debug, Default)]
+pub struct SparseHeatmap {
+    counts: PointHashMap<u64>,
+}
+
+impl SparseHeatmap {
+    pub fn record(&mut self, p: &Point) {
+        *self.counts.entry(p.clone()).or_insert(0) += 1;
+    }
+
+    /// Formats the counts of the bounding box of all active cells as comma
+    /// separated rows.
+    pub fn to_csv(&self) -> String {
+        if self.counts.is_empty() {
+            return String::new();
+        }
+
+        let min_x = self.counts.keys().map(|p| p.x).min().unwrap();
+        let max_x = self.counts.keys().map(|p| p.x).max().unwrap();
+        let min_y = self.counts.keys().map(|p| p.y).min().unwrap();
+        let max_y = self.counts.keys().map(|p| p.y).max().unwrap();
+
+        to_csv(
+            &Point::new(min_x, min_y),
+            &Point::new(max_x - min_x + 1, max_y - min_y + 1),
+            |p| self.counts.get(p).copied().unwrap_or(0),
+        )
+    }
+}
+
+fn to_csv(origin: &Point, size: &Point, count: impl Fn(&Point) -> u64) -> String {
+    let mut csv = String::new();
+    for y in origin.y..origin.y + size.height() {
+        let row: Vec<String> = (origin.x..origin.x + size.width())
+            .map(|x| count(&Point::new(x, y)).to_string())
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_exports_the_bounding_box_of_active_cells() {
+        let mut heatmap = SparseHeatmap::default();
+        heatmap.record(&Point::new(-1, 5));
+        heatmap.record(&Point::new(1, 6));
+        heatmap.record(&Point::new(1, 6));
+
+        assert_eq!(heatmap.to_csv(), "1,0,0\n0,0,2\n");
+        assert_eq!(SparseHeatmap::default().to_csv(), "");
+    }
+
+    #[test]
+    fn it_scales_counts_logarithmically() {
+        let mut heatmap = Heatmap::default();
+        heatmap.resize(&Point::new(3, 1));
+        for _ in 0..3 {
+            heatmap.record(0);
+        }
+        heatmap.record(1);
+
+        assert_eq!(heatmap.maximum, 3);
+        assert_eq!(heatmap.intensity(&Point::new(0, 0)), 1.0);
+        assert_eq!(heatmap.intensity(&Point::new(1, 0)), 0.5);
+        assert_eq!(heatmap.intensity(&Point::new(2, 0)), 0.0);
+        assert_eq!(heatmap.to_csv(), "3,1,0\n");
+
+        heatmap.resize(&Point::new(1, 2));
+        assert_eq!(heatmap.to_csv(), "3\n0\n");
+        heatmap.clear();
+        assert_eq!(heatmap.maximum, 0);
+    }
+}
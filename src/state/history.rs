@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+
+use super::statistics::Statistics;
+
+pub const HISTORY_CAPACITY: usize = 4096;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sample {
+    pub births: usize,
+    pub deaths: usize,
+    pub population: usize,
+}
+
+impl From<&Statistics> for Sample {
+    fn from(statistics: &Statistics) -> Self {
+        Self {
+            births: statistics.births,
+            deaths: statistics.deaths,
+            population: statistics.population,
+        }
+    }
+}
+
+/// A ring buffer of the statistics of the most recent generations.
+#[derive(Debug)]
+pub struct History {
+    capacity: usize,
+    samples: VecDeque<Sample>,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, sample: Sample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Returns up to `n` of the most recent samples, oldest first.
+    pub fn latest(&self, n: usize) -> impl Iterator<Item = &Sample> {
+        self.samples
+            .iter()
+            .skip(self.samples.len().saturating_sub(n))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample(population: usize) -> Sample {
+        Sample {
+            population,
+            ..Sample::default()
+        }
+    }
+
+    #[test]
+    fn it_drops_the_oldest_samples_when_full() {
+        let mut history = History::new(3);
+        for population in 0..5 {
+            history.push(sample(population));
+        }
+
+        let populations: Vec<usize> = history.latest(10).map(|s| s.population).collect();
+        assert_eq!(populations, [2, 3, 4]);
+    }
+
+    #[test]
+    fn it_returns_the_latest_samples_oldest_first() {
+        let mut history = History::new(10);
+        for population in 0..5 {
+            history.push(sample(population));
+        }
+
+        let populations: Vec<usize> = history.latest(2).map(|s| s.population).collect();
+        assert_eq!(populations, [3, 4]);
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use term2d::model::point::Point;
+
+use super::cell_image::CellImage;
+use super::rules::Rules;
+use super::universe::Universe;
+
+pub const IDENTIFICATION_MAX_GENERATIONS: u64 = 1024;
+pub const IDENTIFICATION_MAX_POPULATION: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Classification {
+    Empty,
+    StillLife,
+    Oscillator { period: u64 },
+    Spaceship { period: u64, dx: i32, dy: i32 },
+}
+
+/// The result of simulating a pattern until it repeats itself.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Identification {
+    pub classification: Classification,
+    /// The generation in which the periodic behaviour starts, 0 if the
+    /// pattern itself is periodic.
+    pub since: u64,
+}
+
+impl Identification {
+    /// Simulates the pattern in an unbounded universe until a generation
+    /// repeats an earlier one up to translation, returns `None` if that does
+    /// not happen within `max_generations` or the population grows beyond
+    /// `IDENTIFICATION_MAX_POPULATION`.
+    pub fn identify(
+        cell_image: &CellImage,
+        rules: &Rules,
+        max_generations: u64,
+    ) -> Option<Identification> {
+        let mut universe = Universe::new(cell_image, rules.clone());
+        let mut seen: HashMap<Vec<Point>, (u64, Point)> = HashMap::new();
+
+        loop {
+            let image = universe.cell_image();
+            let origin = Point::new(
+                universe
+                    .living_points
+                    .iter()
+                    .map(|p| p.x)
+                    .min()
+                    .unwrap_or(0),
+                universe
+                    .living_points
+                    .iter()
+                    .map(|p| p.y)
+                    .min()
+                    .unwrap_or(0),
+            );
+
+            if image.living_points.is_empty() {
+                return Some(Identification {
+                    classification: Classification::Empty,
+                    since: universe.generation,
+                });
+            }
+
+            if let Some((generation, earlier_origin)) = seen.get(&image.living_points) {
+                let period = universe.generation - generation;
+                let dx = origin.x - earlier_origin.x;
+                let dy = origin.y - earlier_origin.y;
+
+                let classification = match (period, dx, dy) {
+                    (1, 0, 0) => Classification::StillLife,
+                    (_, 0, 0) => Classification::Oscillator { period },
+                    _ => Classification::Spaceship { period, dx, dy },
+                };
+
+                return Some(Identification {
+                    classification,
+                    since: *generation,
+                });
+            }
+
+            if universe.generation >= max_generations
+                || image.living_points.len() > IDENTIFICATION_MAX_POPULATION
+            {
+                return None;
+            }
+
+            seen.insert(image.living_points, (universe.generation, origin));
+            universe.update();
+        }
+    }
+}
+
+impl Display for Identification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.classification {
+            Classification::Empty => write!(f, "dies out in generation {}", self.since),
+            _ if self.since > 0 => {
+                write!(f, "{} from generation {}", self.classification, self.since)
+            }
+            _ => write!(f, "{}", self.classification),
+        }
+    }
+}
+
+impl Display for Classification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Classification::Empty => write!(f, "empty"),
+            Classification::StillLife => write!(f, "still life"),
+            Classification::Oscillator { period } => write!(f, "oscillator with period {period}"),
+            Classification::Spaceship { period, dx, dy } => write!(
+                f,
+                "spaceship ({dx}, {dy})/{period}, {}",
+                Self::velocity(*period, *dx, *dy)
+            ),
+        }
+    }
+}
+
+impl Classification {
+    /// Formats the velocity in c-notation, e.g. `c/4 diagonal` for a glider.
+    pub fn velocity(period: u64, dx: i32, dy: i32) -> String {
+        let a = dx.unsigned_abs() as u64;
+        let b = dy.unsigned_abs() as u64;
+        let (a, b) = (a.max(b), a.min(b));
+
+        let divisor = gcd(gcd(a, b), period);
+        let period = period / divisor;
+        let (a, b) = (a / divisor, b / divisor);
+
+        let c = |n: u64| match (n, period) {
+            (1, 1) => "c".to_string(),
+            (n, 1) => format!("{n}c"),
+            (1, p) => format!("c/{p}"),
+            (n, p) => format!("{n}c/{p}"),
+        };
+
+        if b == 0 {
+            format!("{} orthogonal", c(a))
+        } else if a == b {
+            format!("{} diagonal", c(a))
+        } else {
+            format!("({a},{b}){} oblique", c(1))
+        }
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn identify(pattern: &str) -> Option<Identification> {
+        Identification::identify(
+            &CellImage::from(pattern),
+            &Rules::parse("B3/S23").unwrap(),
+            IDENTIFICATION_MAX_GENERATIONS,
+        )
+    }
+
+    #[test]
+    fn it_identifies_still_lifes_and_oscillators() {
+        let block = identify("OO\nOO").unwrap();
+        assert_eq!(block.classification, Classification::StillLife);
+
+        let blinker = identify("OOO").unwrap();
+        assert_eq!(
+            blinker.classification,
+            Classification::Oscillator { period: 2 }
+        );
+        assert_eq!(blinker.since, 0);
+    }
+
+    #[test]
+    fn it_identifies_spaceships() {
+        let glider = identify(".O.\n..O\nOOO").unwrap();
+        assert_eq!(
+            glider.classification,
+            Classification::Spaceship {
+                period: 4,
+                dx: 1,
+                dy: 1
+            }
+        );
+
+        let lwss = identify(".O..O\nO....\nO...O\nOOOO.").unwrap();
+        assert_eq!(lwss.to_string(), "spaceship (-2, 0)/4, c/2 orthogonal");
+    }
+
+    #[test]
+    fn it_identifies_patterns_which_die_out_or_stabilize_later() {
+        let domino = identify("OO").unwrap();
+        assert_eq!(domino.to_string(), "dies out in generation 1");
+
+        let pre_block = identify("OO\nO.").unwrap();
+        assert_eq!(pre_block.to_string(), "still life from generation 1");
+    }
+
+    #[test]
+    fn it_gives_up_after_the_maximum_generations() {
+        let r_pentonimo = Identification::identify(
+            &CellImage::from(".OO\nOO.\n.O."),
+            &Rules::parse("B3/S23").unwrap(),
+            100,
+        );
+        assert_eq!(r_pentonimo, None);
+    }
+
+    #[test]
+    fn it_formats_velocities_in_c_notation() {
+        assert_eq!(Classification::velocity(4, 1, 1), "c/4 diagonal");
+        assert_eq!(Classification::velocity(4, 0, -2), "c/2 orthogonal");
+        assert_eq!(Classification::velocity(5, 2, 0), "2c/5 orthogonal");
+        assert_eq!(Classification::velocity(6, 2, -1), "(2,1)c/6 oblique");
+        assert_eq!(Classification::velocity(1, 1, 0), "c orthogonal");
+    }
+}
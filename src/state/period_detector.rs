@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+
+pub const PERIOD_DETECTOR_CAPACITY: usize = 1024;
+
+/// Detects when the world becomes static or periodic by remembering the
+/// hashes of the most recent generations.
+#[derive(Debug)]
+pub struct PeriodDetector {
+    capacity: usize,
+    hashes: VecDeque<u64>,
+    pub period: Option<u64>,
+    pub stable_since: Option<u64>,
+}
+
+impl PeriodDetector {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            hashes: VecDeque::with_capacity(capacity),
+            period: None,
+            stable_since: None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.hashes.clear();
+        self.period = None;
+        self.stable_since = None;
+    }
+
+    /// Registers the hash of the specified generation and returns the period
+    /// if the same hash was seen before.
+    pub fn push(&mut self, generation: u64, hash: u64) -> Option<u64> {
+        let period = self
+            .hashes
+            .iter()
+            .rev()
+            .position(|&h| h == hash)
+            .map(|i| i as u64 + 1);
+
+        if period.is_none() {
+            self.stable_since = None;
+        } else if self.period != period || self.stable_since.is_none() {
+            self.stable_since = Some(generation);
+        }
+        self.period = period;
+
+        if self.hashes.len() == self.capacity {
+            self.hashes.pop_front();
+        }
+        self.hashes.push_back(hash);
+
+        period
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_detects_a_static_world() {
+        let mut detector = PeriodDetector::new(8);
+        assert_eq!(detector.push(0, 5), None);
+        assert_eq!(detector.push(1, 7), None);
+        assert_eq!(detector.push(2, 7), Some(1));
+        assert_eq!(detector.period, Some(1));
+        assert_eq!(detector.stable_since, Some(2));
+    }
+
+    #[test]
+    fn it_detects_the_period_of_an_oscillating_world() {
+        let mut detector = PeriodDetector::new(8);
+        for (generation, hash) in [1, 2, 3, 4, 2, 3, 4, 2].into_iter().enumerate() {
+            detector.push(generation as u64, hash);
+        }
+        assert_eq!(detector.period, Some(3));
+        assert_eq!(detector.stable_since, Some(4));
+    }
+
+    #[test]
+    fn it_forgets_hashes_beyond_its_capacity() {
+        let mut detector = PeriodDetector::new(2);
+        detector.push(0, 1);
+        detector.push(1, 2);
+        detector.push(2, 3);
+        assert_eq!(detector.push(3, 1), None);
+        assert_eq!(detector.stable_since, None);
+    }
+}
@@ -3,14 +3,51 @@ use clap::Error;
 use clap::ErrorKind;
 use clap::Parser;
 use regex::Regex;
+use std::fmt::Display;
 
-#[derive(Clone, Debug, Parser)]
+/// Well known life-like rules which can be cycled through at runtime.
+pub const RULES_PRESETS: [(&str, &str); 10] = [
+    ("B3/S23", "conway's life"),
+    ("B36/S23", "highlife"),
+    ("B3678/S34678", "day & night"),
+    ("B2/S", "seeds"),
+    ("B1357/S1357", "replicator"),
+    ("B368/S245", "morley"),
+    ("B3/S12345", "maze"),
+    ("B34/S34", "34 life"),
+    ("B36/S125", "2x2"),
+    ("B3/S012345678", "life without death"),
+];
+
+#[derive(Clone, Debug, Parser, PartialEq)]
 pub struct Rules {
     pub birth: Vec<u32>,
     pub survival: Vec<u32>,
 }
 
+impl Display for Rules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let birth: String = self.birth.iter().map(|d| d.to_string()).collect();
+        let survival: String = self.survival.iter().map(|d| d.to_string()).collect();
+        write!(f, "B{birth}/S{survival}")
+    }
+}
+
 impl Rules {
+    pub fn preset(index: usize) -> Rules {
+        Rules::parse(RULES_PRESETS[index % RULES_PRESETS.len()].0).unwrap()
+    }
+
+    pub fn preset_index(&self) -> Option<usize> {
+        RULES_PRESETS
+            .iter()
+            .position(|(rules, _)| Rules::parse(rules).ok().as_ref() == Some(self))
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        self.preset_index().map(|i| RULES_PRESETS[i].1)
+    }
+
     pub fn parse(rules: &str) -> Result<Rules, Error> {
         let mut birth = Vec::new();
         let mut survival = Vec::new();
@@ -51,6 +88,25 @@ mod test {
         assert_eq!(survival, [0, 1, 2, 3, 4, 5, 6, 7, 8]);
     }
 
+    #[test]
+    fn it_formats_rules_in_the_b_s_notation() {
+        assert_eq!(Rules::parse("B36/S23").unwrap().to_string(), "B36/S23");
+        assert_eq!(Rules::parse("B2/S").unwrap().to_string(), "B2/S");
+    }
+
+    #[test]
+    fn it_finds_the_name_of_a_preset() {
+        assert_eq!(Rules::parse("B36/S23").unwrap().name(), Some("highlife"));
+        assert_eq!(Rules::parse("B1/S1").unwrap().name(), None);
+    }
+
+    #[test]
+    fn it_parses_all_presets() {
+        for i in 0..RULES_PRESETS.len() {
+            assert_eq!(Rules::preset(i).preset_index(), Some(i));
+        }
+    }
+
     #[test]
     fn it_fails_when_the_regex_is_not_matched() {
         let err = Rules::parse("nonsense");
@@ -0,0 +1,97 @@
+use std::cmp::max;
+use std::cmp::min;
+
+use term2d::model::point::Point;
+use term2d::model::rect::Rect;
+
+/// Statistics about the living cells of the current generation, they are
+/// accumulated while the world is updated.
+#[derive(Clone, Default, PartialEq)]
+pub struct Statistics {
+    pub births: usize,
+    pub bounding_box: Option<Rect>,
+    pub deaths: usize,
+    pub hash: u64,
+    pub population: usize,
+}
+
+impl Statistics {
+    pub fn add_living(&mut self, p: &Point) {
+        self.population += 1;
+        self.hash = self.hash.wrapping_add(Self::hash_point(p));
+
+        self.bounding_box = Some(match self.bounding_box.take() {
+            None => Rect::new(p.x, p.y, 1, 1),
+            Some(Rect { pos, size }) => {
+                let x0 = min(pos.x, p.x);
+                let y0 = min(pos.y, p.y);
+                let x1 = max(pos.x + size.width(), p.x + 1);
+                let y1 = max(pos.y + size.height(), p.y + 1);
+                Rect::new(x0, y0, x1 - x0, y1 - y0)
+            }
+        });
+    }
+
+    /// Mixes the coordinates with splitmix64, summing the results gives a hash
+    /// of the living cells which is independent of the order they are added.
+    fn hash_point(p: &Point) -> u64 {
+        let mut z =
+            ((p.x as u32 as u64) << 32 | p.y as u32 as u64).wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn density(&self, size: &Point) -> f32 {
+        let area = size.width() * size.height();
+        if area == 0 {
+            return 0.0;
+        }
+
+        self.population as f32 / area as f32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_grows_the_bounding_box_with_living_cells() {
+        let mut statistics = Statistics::default();
+        statistics.add_living(&Point::new(3, 4));
+        assert!(statistics.bounding_box == Some(Rect::new(3, 4, 1, 1)));
+
+        statistics.add_living(&Point::new(1, 6));
+        statistics.add_living(&Point::new(2, 5));
+        assert!(statistics.bounding_box == Some(Rect::new(1, 4, 3, 3)));
+        assert_eq!(statistics.population, 3);
+    }
+
+    #[test]
+    fn it_hashes_living_cells_independent_of_their_order() {
+        let mut a = Statistics::default();
+        a.add_living(&Point::new(1, 2));
+        a.add_living(&Point::new(2, 1));
+
+        let mut b = Statistics::default();
+        b.add_living(&Point::new(2, 1));
+        b.add_living(&Point::new(1, 2));
+
+        let mut c = Statistics::default();
+        c.add_living(&Point::new(1, 1));
+        c.add_living(&Point::new(2, 2));
+
+        assert_eq!(a.hash, b.hash);
+        assert_ne!(a.hash, c.hash);
+    }
+
+    #[test]
+    fn it_calculates_the_density() {
+        let mut statistics = Statistics::default();
+        assert_eq!(statistics.density(&Point::new(0, 0)), 0.0);
+
+        statistics.add_living(&Point::new(0, 0));
+        assert_eq!(statistics.density(&Point::new(2, 2)), 0.25);
+    }
+}
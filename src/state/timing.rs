@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+/// Exponentially smoothed durations of the phases of a frame, in
+/// milliseconds.
+#[derive(Clone, Debug, Default)]
+pub struct Timing {
+    /// How far the interval between frames is off from the target interval.
+    pub jitter_ms: f32,
+    pub render_ms: f32,
+    pub update_ms: f32,
+}
+
+impl Timing {
+    pub fn record_jitter(&mut self, interval: Duration, target: Duration) {
+        let jitter = interval.as_secs_f32() - target.as_secs_f32();
+        smooth(&mut self.jitter_ms, 1000.0 * jitter.abs());
+    }
+
+    pub fn record_render(&mut self, duration: Duration) {
+        smooth(&mut self.render_ms, 1000.0 * duration.as_secs_f32());
+    }
+
+    pub fn record_update(&mut self, duration: Duration) {
+        smooth(&mut self.update_ms, 1000.0 * duration.as_secs_f32());
+    }
+}
+
+fn smooth(average: &mut f32, value: f32) {
+    *average = 0.9 * *average + 0.1 * value;
+}
@@ -0,0 +1,104 @@
+use term2d::model::point::Point;
+
+use crate::common::point_hash::PointHashMap;
+use crate::common::point_hash::PointHashSet;
+
+use super::cell_image::CellImage;
+use super::rules::Rules;
+
+/// An unbounded universe which only keeps track of its living cells, in
+/// contrast to the toroidal `World` it does not depend on a screen size.
+/// Births are only considered next to living cells, so B0 rules are not
+/// simulated faithfully.
+#[derive(Clone, Debug)]
+pub struct Universe {
+    pub generation: u64,
+    pub living_points: PointHashSet,
+    pub rules: Rules,
+}
+
+impl Universe {
+    pub fn new(cell_image: &CellImage, rules: Rules) -> Self {
+        Self {
+            generation: 0,
+            living_points: cell_image.living_points.iter().cloned().collect(),
+            rules,
+        }
+    }
+
+    pub fn update(&mut self) {
+        let mut neighbour_counts: PointHashMap<u32> = PointHashMap::with_capacity_and_hasher(
+            4 * self.living_points.len(),
+            Default::default(),
+        );
+
+        for p in &self.living_points {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx != 0 || dy != 0 {
+                        *neighbour_counts
+                            .entry(Point::new(p.x + dx, p.y + dy))
+                            .or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        // isolated cells have no entry yet but may survive with S0
+        for p in &self.living_points {
+            neighbour_counts.entry(p.clone()).or_insert(0);
+        }
+
+        self.living_points = neighbour_counts
+            .into_iter()
+            .filter(|(p, count)| {
+                if self.living_points.contains(p) {
+                    self.rules.survival.contains(count)
+                } else {
+                    self.rules.birth.contains(count)
+                }
+            })
+            .map(|(p, _)| p)
+            .collect();
+
+        self.generation += 1;
+    }
+
+    pub fn cell_image(&self) -> CellImage {
+        let points: Vec<Point> = self.living_points.iter().cloned().collect();
+        CellImage::from_points(&points)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn conway(pattern: &str) -> Universe {
+        Universe::new(&CellImage::from(pattern), Rules::parse("B3/S23").unwrap())
+    }
+
+    #[test]
+    fn it_oscillates_a_blinker() {
+        let mut universe = conway("OOO");
+        universe.update();
+        assert_eq!(universe.cell_image().size, Point::new(1, 3));
+        universe.update();
+        assert_eq!(universe.cell_image().size, Point::new(3, 1));
+        assert_eq!(universe.generation, 2);
+    }
+
+    #[test]
+    fn it_moves_a_glider_without_boundaries() {
+        let mut universe = conway(".O.\n..O\nOOO");
+        let start = universe.cell_image().living_points;
+
+        for _ in 0..400 {
+            universe.update();
+        }
+
+        assert_eq!(universe.living_points.len(), 5);
+        assert_eq!(universe.cell_image().living_points, start);
+        assert!(universe.living_points.contains(&Point::new(101, 100)));
+    }
+}
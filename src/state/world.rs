@@ -1,7 +1,10 @@
+use std::cmp::min;
+
 use nanorand::Rng;
 use nanorand::WyRand;
 
 use crate::common::args::Args;
+use crate::common::gradient::Gradient;
 use crate::common::rainbow::Rainbow;
 use term2d::model::color::Color;
 use term2d::model::point::Point;
@@ -10,74 +13,122 @@ use term2d::model::rgba::Rgba;
 use super::cell::Cell;
 use super::cell_image::CellImage;
 use super::cell_setup::CellSetup;
+use super::heatmap::Heatmap;
+use super::rules::Rules;
+use super::statistics::Statistics;
 
 pub struct World {
-    pub birth_rule: Vec<u32>,
+    pub age_gradient: Gradient,
     pub cell_setup: CellSetup,
+    /// The seed of random cells, the size of a screen saver soup is drawn
+    /// from the seed before it.
+    cell_seed: u64,
     pub cells: Vec<Cell>,
     // TODO
     pub color_alpha: u8,
     pub color_bg_alive: Rgba,
     pub color_bg_dead: Rgba,
+    pub color_by_age: bool,
     pub fading_speed: i32,
+    pub generation: u64,
+    pub heatmap: Heatmap,
     pub rainbow: Option<Rainbow>,
+    pub rules: Rules,
+    pub seed: u64,
     pub size: Point,
-    pub survival_rule: Vec<u32>,
+    pub statistics: Statistics,
 }
 
 impl From<Args> for World {
-    fn from(args: Args) -> Self {
-        let (color_alpha, cell_setup) = if args.screen_saver.is_some() {
-            let mut rng = WyRand::new();
+    fn from(mut args: Args) -> Self {
+        let seed = args.resolve_seed();
+        let rainbow = args.rainbow.then(|| Rainbow::from(&args));
+
+        let (color_alpha, cell_setup, cell_seed) = if args.screen_saver.is_some() {
+            let mut rng = WyRand::new_seed(seed);
             let width = rng.generate_range(15_i32..=75);
             let height = rng.generate_range(5_i32..=25);
-            (0, CellSetup::rect_soup(width, height))
-        } else {
-            (255, args.cell_setup)
-        };
-
-        let rainbow = if args.rainbow {
-            Some(Rainbow::new(vec![
-                Rgba::red(),
-                Rgba::yellow(),
-                Rgba::green(),
-                Rgba::cyan(),
-                Rgba::blue(),
-                Rgba::violet(),
-            ]))
+            (0, CellSetup::RectSoup(width, height), rng.generate::<u64>())
         } else {
-            None
+            (255, args.cell_setup, seed)
         };
 
         Self {
-            birth_rule: args.rules.birth.clone(),
+            age_gradient: args.age_gradient,
             cell_setup,
+            cell_seed,
             cells: Vec::new(),
             color_alpha,
             color_bg_alive: args.color_bg_alive,
             color_bg_dead: args.color_bg_dead,
+            color_by_age: args.color_by_age,
             fading_speed: args.fading_speed,
+            generation: 0,
+            heatmap: Heatmap::default(),
             rainbow,
+            rules: args.rules,
+            seed,
             size: Point::new(0, 0),
-            survival_rule: args.rules.survival.clone(),
+            statistics: Statistics::default(),
         }
     }
 }
 
 impl World {
+    pub fn set_rainbow(&mut self, mut rainbow: Option<Rainbow>) {
+        if let Some(rainbow) = &mut rainbow {
+            rainbow.resize(&self.size);
+        }
+        self.rainbow = rainbow;
+        self.recolor();
+    }
+
+    /// Moves an animated rainbow one frame further.
+    pub fn drift_rainbow(&mut self) {
+        if self.rainbow.as_mut().is_some_and(|rainbow| rainbow.drift()) {
+            self.recolor();
+        }
+    }
+
+    /// Reapplies the current colors to all living cells, dead cells keep
+    /// fading towards the dead color.
+    pub fn recolor(&mut self) {
+        for i in 0..self.cells.len() {
+            if self.cells[i].alive {
+                self.color_alive(i);
+            }
+        }
+    }
+
+    /// Changes the size of the world, cells outside of the new size are
+    /// removed and new cells are dead.
     pub fn resize(&mut self, size: &Point) {
-        self.size = size.clone();
+        let old_size = std::mem::replace(&mut self.size, size.clone());
+        let old_cells = std::mem::take(&mut self.cells);
 
         if let Some(rainbow) = &mut self.rainbow {
             rainbow.resize(size);
         }
-        self.setup_cells();
+        self.heatmap.resize(size);
+        self.setup_blank();
+
+        if !old_cells.is_empty() {
+            for y in 0..min(old_size.height(), size.height()) {
+                for x in 0..min(old_size.width(), size.width()) {
+                    self.cells[(size.width() * y + x) as usize] =
+                        old_cells[(old_size.width() * y + x) as usize].clone();
+                }
+            }
+        }
+        self.recount_statistics();
     }
 
     pub fn setup_cells(&mut self) {
         self.setup_blank();
+        self.generation = 0;
+        self.heatmap.clear();
 
-        let cell_image = CellImage::from(self.cell_setup.clone());
+        let cell_image = CellImage::from_cell_setup(&self.cell_setup, self.cell_seed);
         let cell_image_pos = self.size.half() - cell_image.size.half();
 
         for p in &cell_image.living_points {
@@ -86,25 +137,75 @@ impl World {
                 self.set_alive_p(point);
             }
         }
+
+        self.recount_statistics();
     }
 
-    pub fn set_alive(&mut self, i: usize) {
-        let bg = if let Some(rainbow) = &self.rainbow {
-            let point = Point::new(i as i32 % self.size.width(), i as i32 / self.size.width());
-            rainbow.at(point)
+    /// Scans all cells to recalculate the statistics, only needed when cells
+    /// are changed outside of `update`.
+    pub fn recount_statistics(&mut self) {
+        self.statistics = Statistics::default();
+
+        for i in 0..self.cells.len() {
+            if self.cells[i].alive {
+                self.statistics.add_living(&self.point_at(i));
+            }
+        }
+    }
+
+    pub fn toggle_life(&mut self, i: usize) {
+        if self.cells[i].alive {
+            self.set_dead(i);
         } else {
-            self.color_bg_alive.clone()
-        };
+            self.set_alive(i);
+        }
 
-        let cell = Cell {
+        self.recount_statistics();
+    }
+
+    fn point_at(&self, i: usize) -> Point {
+        Point::new(i as i32 % self.size.width(), i as i32 / self.size.width())
+    }
+
+    /// The color a cell is shown in, blended over the dead color.
+    pub fn visible_color(&self, i: usize) -> Rgba {
+        self.cells[i].color.bg.blend(&self.color_bg_dead)
+    }
+
+    pub fn cell_image(&self) -> CellImage {
+        let living_points = (0..self.cells.len())
+            .filter(|&i| self.cells[i].alive)
+            .map(|i| self.point_at(i))
+            .collect();
+
+        CellImage {
+            living_points,
+            size: self.size.clone(),
+        }
+    }
+
+    pub fn set_alive(&mut self, i: usize) {
+        self.cells[i] = Cell {
+            age: 0,
             alive: true,
-            color: Color {
-                fg: Rgba::default(),
-                bg,
-            },
+            color: Color::default(),
         };
+        self.color_alive(i);
+    }
 
-        self.cells[i] = cell;
+    fn survive(&mut self, i: usize) {
+        self.cells[i].age += 1;
+        self.color_alive(i);
+    }
+
+    fn color_alive(&mut self, i: usize) {
+        self.cells[i].color.bg = if self.color_by_age {
+            self.age_gradient.at(self.cells[i].age as f32)
+        } else if let Some(rainbow) = &self.rainbow {
+            rainbow.at(self.point_at(i))
+        } else {
+            self.color_bg_alive.clone()
+        };
     }
 
     fn set_alive_p(&mut self, p: Point) {
@@ -113,6 +214,7 @@ impl World {
 
     pub fn set_dead(&mut self, i: usize) {
         let cell = Cell {
+            age: 0,
             alive: false,
             color: Color {
                 fg: Rgba::default(),
@@ -123,7 +225,9 @@ impl World {
         self.cells[i] = cell;
     }
 
+    /// Kills the cell, its last color fades towards the dead color.
     pub fn set_dead_fading(&mut self, i: usize) {
+        self.cells[i].age = 0;
         self.cells[i].alive = false;
         self.cells[i]
             .color
@@ -133,6 +237,7 @@ impl World {
 
     fn setup_blank(&mut self) {
         let dead_cell = Cell {
+            age: 0,
             alive: false,
             color: Color {
                 fg: Rgba::default(),
@@ -150,76 +255,61 @@ impl World {
                 let i = (self.size.width() * y + x) as usize;
                 let neighbour_cell_count = self.count_neighbor_cells(&Point::new(x, y));
 
-                if self.cells[i].alive {
-                    if self
-                        .survival_rule
-                        .iter()
-                        .find(|&&count| count == neighbour_cell_count)
-                        .is_some()
-                    {
-                        is_alive[i] = true;
-                    } else {
-                        is_alive[i] = false;
-                    }
+                is_alive[i] = if self.cells[i].alive {
+                    self.rules.survival.contains(&neighbour_cell_count)
                 } else {
-                    if self
-                        .birth_rule
-                        .iter()
-                        .find(|&&count| count == neighbour_cell_count)
-                        .is_some()
-                    {
-                        is_alive[i] = true;
-                    } else {
-                        is_alive[i] = false;
-                    }
-                }
+                    self.rules.birth.contains(&neighbour_cell_count)
+                };
             }
         }
 
-        for i in 0..self.cells.len() {
-            if is_alive[i] {
-                self.set_alive(i);
+        let mut statistics = Statistics::default();
+
+        for (i, alive) in is_alive.into_iter().enumerate() {
+            if alive || self.cells[i].alive {
+                self.heatmap.record(i);
+            }
+
+            if alive {
+                statistics.add_living(&self.point_at(i));
+                if self.cells[i].alive {
+                    self.survive(i);
+                } else {
+                    statistics.births += 1;
+                    self.set_alive(i);
+                }
             } else {
+                if self.cells[i].alive {
+                    statistics.deaths += 1;
+                }
                 self.set_dead_fading(i);
             }
         }
+
+        self.statistics = statistics;
+        self.generation += 1;
     }
 
     fn count_neighbor_cells(&self, p: &Point) -> u32 {
         let mut count = 0;
-        let neighbour_indices = self.get_neighbour_indices(p);
 
-        for index in neighbour_indices {
-            if self.cells[index].alive {
-                count += 1;
+        for dy in -1..=1 {
+            let y = Self::toroidal_mod(p.y + dy, self.size.height());
+            for dx in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+
+                let x = Self::toroidal_mod(p.x + dx, self.size.width());
+                if self.cells[(self.size.width() * y + x) as usize].alive {
+                    count += 1;
+                }
             }
         }
 
         count
     }
 
-    fn get_neighbour_indices(&self, p: &Point) -> Vec<usize> {
-        vec![
-            self.transform_toroidal_point(&p.left()),
-            self.transform_toroidal_point(&p.right()),
-            self.transform_toroidal_point(&p.up()),
-            self.transform_toroidal_point(&p.up_left()),
-            self.transform_toroidal_point(&p.up_right()),
-            self.transform_toroidal_point(&p.down()),
-            self.transform_toroidal_point(&p.down_left()),
-            self.transform_toroidal_point(&p.down_right()),
-        ]
-        .iter()
-        .map(|p| (self.size.width() * p.y + p.x) as usize)
-        .collect()
-    }
-
-    fn transform_toroidal_point(&self, p: &Point) -> Point {
-        let new_x = Self::toroidal_mod(p.x, self.size.width());
-        let new_y = Self::toroidal_mod(p.y, self.size.height());
-        Point::new(new_x, new_y)
-    }
-
     fn toroidal_mod(n: i32, m: i32) -> i32 {
         let modulus = n % m;
 
@@ -229,3 +319,56 @@ impl World {
         modulus
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_ages_surviving_cells() {
+        let mut world = World::from(Args {
+            cell_setup: CellSetup::Special(".....\n.....\n.OOO.\n.....\n.....".to_string()),
+            ..Args::default()
+        });
+        world.resize(&Point::new(5, 5));
+        world.setup_cells();
+        world.update();
+        world.update();
+
+        let ages: Vec<u64> = world
+            .cells
+            .iter()
+            .filter(|cell| cell.alive)
+            .map(|cell| cell.age)
+            .collect();
+        assert_eq!(ages, [0, 2, 0]);
+    }
+
+    #[test]
+    fn it_keeps_the_cells_which_fit_when_resized() {
+        let mut world = World::from(Args {
+            cell_setup: CellSetup::Special("O...\n....\n...O".to_string()),
+            ..Args::default()
+        });
+        world.resize(&Point::new(4, 3));
+        world.setup_cells();
+        world.resize(&Point::new(4, 2));
+
+        assert_eq!(world.cells.len(), 8);
+        assert!(world.cells[0].alive);
+        assert_eq!(world.statistics.population, 1);
+    }
+
+    #[test]
+    fn it_draws_screen_saver_cells_independently_of_their_size() {
+        let args = Args {
+            screen_saver: Some(10),
+            seed: Some(7),
+            ..Args::default()
+        };
+        let world = World::from(args.clone());
+
+        assert_ne!(world.cell_seed, world.seed);
+        assert_eq!(world.cell_seed, World::from(args).cell_seed);
+    }
+}